@@ -1,21 +1,172 @@
 use std::env;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
 
-use ember::Manager;
+use ember::ApplicationConfig;
+use bevy_reflect::TypeRegistryArc;
+use ember::core::scene::{DynamicScene, MigrationRegistry, Scene, SceneDiff, Staged, TypeRegistryResource};
+use ember::core::scene::scene_format::{convert_scene, parse_scene};
 
 use log::LevelFilter;
 
+const USAGE: &str = "Usage: ember [OPTIONS]
+       ember convert <in> <out>
+       ember diff <old> <new>
+
+Commands:
+    convert <in> <out>          Convert a scene between .ron and binary .scn, picked by extension.
+                                Only engine components are known, not ones from plugins
+    diff <old> <new>            Print the changes between two scene files as a ron patch
+
+Options:
+    --project <dir>             Open the project in <dir> on startup
+    --scene <name.ron|name.scn> Scene in <project>/scenes to open (default: the project's default scene)
+    --log-level <level>         off, error, warn, info, debug or trace (default: info)
+    --log-module <mod>=<level>  Log level for a single module, ie vulkano=warn. Can be repeated
+    --frames <n>                Exit after <n> frames
+    --headless                  Run without a window. Requires --frames
+    --screenshot <file.png>     Save the last frame (or the first if --frames isn't set) to a png
+    --tick-rate <hz>            Fixed update ticks per second (default: 25)
+    -h, --help                  Print this message
+";
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level).map_err(|_| format!("'{}' is not a valid log level", level))
+}
+
+fn parse_args(args: &[String]) -> Result<ApplicationConfig, String> {
+    let mut config = ApplicationConfig::default();
+    let mut args = args.iter();
+
+    // pulls the value for a flag off the iterator
+    fn value<'a>(flag: &str, args: &mut std::slice::Iter<'a, String>) -> Result<&'a String, String> {
+        args.next().ok_or_else(|| format!("{} expects a value", flag))
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => {
+                config = config.with_project(PathBuf::from(value(arg, &mut args)?));
+            },
+            "--scene" => {
+                config = config.with_scene(value(arg, &mut args)?.clone());
+            },
+            "--log-level" => {
+                config = config.with_log_level(parse_level(value(arg, &mut args)?)?);
+            },
+            "--log-module" => {
+                let filter = value(arg, &mut args)?;
+                let (module, level) = filter
+                    .split_once('=')
+                    .ok_or_else(|| format!("--log-module expects <module>=<level>, got '{}'", filter))?;
+                config = config.with_module_log_level(module, parse_level(level)?);
+            },
+            "--frames" => {
+                let frames = value(arg, &mut args)?;
+                let frames = frames
+                    .parse::<u64>()
+                    .map_err(|_| format!("--frames expects a number, got '{}'", frames))?;
+                config = config.with_frames(frames);
+            },
+            "--headless" => {
+                config = config.headless(true);
+            },
+            "--screenshot" => {
+                config = config.with_screenshot(PathBuf::from(value(arg, &mut args)?));
+            },
+            "--tick-rate" => {
+                let rate = value(arg, &mut args)?;
+                let rate = rate
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|r| *r > 0)
+                    .ok_or_else(|| format!("--tick-rate expects a positive number, got '{}'", rate))?;
+                config = config.with_tick_rate(rate);
+            },
+            // old style `ember debug`
+            "debug" => {
+                config = config.with_log_level(LevelFilter::Debug);
+            },
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    if config.scene.is_some() && config.project.is_none() {
+        return Err(String::from("--scene requires --project"));
+    }
+    if config.headless && config.frames.is_none() {
+        return Err(String::from("--headless requires --frames"));
+    }
+    if config.headless && config.screenshot.is_some() {
+        return Err(String::from("--screenshot needs a window and can't be used with --headless"));
+    }
+
+    Ok(config)
+}
+
+// converts a scene file using the engine's type registry
+fn convert(args: &[String]) -> Result<(), String> {
+    let (input, output) = match args {
+        [input, output] => (input, output),
+        _ => return Err(String::from("convert expects <in> <out>")),
+    };
+    let mut scene = Scene::<Staged>::new();
+    let registry = scene.get_world().unwrap().resource::<TypeRegistryResource>().0.clone();
+    convert_scene(input, output, &registry, &MigrationRegistry::new()).map_err(|e| e.to_string())
+}
+
+fn read_scene(path: &str, registry: &TypeRegistryArc) -> Result<DynamicScene, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+    parse_scene(path, &bytes, registry, &MigrationRegistry::new()).map_err(|e| e.to_string())
+}
+
+// prints what changed between two scene files, ie for reviewing scene changes
+fn diff(args: &[String]) -> Result<(), String> {
+    let (old, new) = match args {
+        [old, new] => (old, new),
+        _ => return Err(String::from("diff expects <old> <new>")),
+    };
+    let mut scene = Scene::<Staged>::new();
+    let registry = scene.get_world().unwrap().resource::<TypeRegistryResource>().0.clone();
+    let diff = SceneDiff::between(&read_scene(old, &registry)?, &read_scene(new, &registry)?, &registry)
+        .map_err(|e| e.to_string())?;
+    println!("{}", diff.to_ron().map_err(|e| e.to_string())?);
+    Ok(())
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let mut log_level = LevelFilter::Info;
-    if args.len() > 1 {
-        if args[1] == "debug" {
-            log_level = LevelFilter::Debug;
-        } else if args[1] == "brooke" {
-            println!("hi brooke");
-        } else {
-            println!("Get fucked that's not a valid log level");
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        print!("{}", USAGE);
+        return;
+    }
+
+    let command: Option<fn(&[String]) -> Result<(), String>> = match args.first().map(String::as_str) {
+        Some("convert") => Some(convert),
+        Some("diff") => Some(diff),
+        _ => None,
+    };
+    if let Some(command) = command {
+        if let Err(e) = command(&args[1..]) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let config = match parse_args(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
         }
+    };
+
+    let mut app: ember::Application = ember::Application::create_application_with_config(config.clone());
+    if config.headless {
+        app.run_headless(config.frames.unwrap());
+    } else {
+        app.run();
     }
-    let mut app: ember::Application = ember::Application::create_application(log_level);
-    app.run();
-}
\ No newline at end of file
+}
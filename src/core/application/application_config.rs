@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use log::LevelFilter;
+
+// everything the application needs to know at launch. the binary fills this in from the
+// command line but it can be built by hand for tests or embedding
+#[derive(Clone, Debug)]
+pub struct ApplicationConfig{
+    pub log_level: LevelFilter,
+    // per module overrides, ie ("vulkano", LevelFilter::Warn)
+    pub module_log_levels: Vec<(String, LevelFilter)>,
+    // project directory to open on startup
+    pub project: Option<PathBuf>,
+    // scene file inside <project>/scenes to open on startup
+    pub scene: Option<String>,
+    // exit after this many frames
+    pub frames: Option<u64>,
+    pub headless: bool,
+    // write the final frame to this png
+    pub screenshot: Option<PathBuf>,
+    // rate of the fixed update schedule
+    pub ticks_per_second: u32,
+}
+
+impl Default for ApplicationConfig{
+    fn default() -> Self {
+        ApplicationConfig{
+            log_level: LevelFilter::Info,
+            module_log_levels: Vec::new(),
+            project: None,
+            scene: None,
+            frames: None,
+            headless: false,
+            screenshot: None,
+            ticks_per_second: 25,
+        }
+    }
+}
+
+impl ApplicationConfig{
+    pub fn with_log_level(mut self, log_level: LevelFilter) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    pub fn with_module_log_level(mut self, module: &str, log_level: LevelFilter) -> Self {
+        self.module_log_levels.push((String::from(module), log_level));
+        self
+    }
+
+    pub fn with_project(mut self, project: PathBuf) -> Self {
+        self.project = Some(project);
+        self
+    }
+
+    pub fn with_scene(mut self, scene: String) -> Self {
+        self.scene = Some(scene);
+        self
+    }
+
+    pub fn with_frames(mut self, frames: u64) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    pub fn with_screenshot(mut self, screenshot: PathBuf) -> Self {
+        self.screenshot = Some(screenshot);
+        self
+    }
+
+    pub fn with_tick_rate(mut self, ticks_per_second: u32) -> Self {
+        self.ticks_per_second = ticks_per_second;
+        self
+    }
+}
@@ -8,7 +8,8 @@ use std::{
     ops::AddAssign,
 };
 use std::ops::DerefMut;
-use std::borrow::Borrow;
+use std::cell::RefMut;
+use std::path::PathBuf;
 
 use egui::Window;
 use ember_math::{Vector4f, Vector2f};
@@ -24,11 +25,37 @@ use crate::core::{
         SceneManagerUpdateResults,
     },
     systems::ui_systems::EguiState,
+    scene::{
+        Scene,
+        Active,
+        ScheduleRegistry,
+        SceneSchedule,
+        MigrationRegistry,
+        TypeRegistryResource,
+    },
 };
 use crate::core::application::{
-    ApplicationState,
     ApplicationIdleState,
+    ApplicationStateStack,
+    ApplicationConfig,
+    FixedTimestep,
+    Interpolation,
+    Time,
+    PlayMode,
+};
+use crate::core::managers::SceneManagerMessagePump;
+use crate::core::events::scene_manager_messages::SceneManagerMessage;
+use crate::core::events::application_state_events::{
+    ApplicationStateEvent,
+    ApplicationStateType,
 };
+use crate::core::events::play_mode_events::PlayModeEvent;
+use crate::core::events::error_events::ErrorEvent;
+use crate::core::project::{CurrentProject, RecentProjects};
+use crate::core::errors::EngineError;
+use crate::core::application::application_states::create_application_state;
+use bevy_ecs::event::Events;
+use bevy_reflect::{TypeRegistry, TypeRegistryArc};
 
 
 // window and event management
@@ -48,6 +75,8 @@ use winit::{
 // egui
 
 use crate::core::plugins::components::AppInterfaceFlag;
+use crate::core::plugins::components::SceneHandle;
+use crate::core::plugins::Plugin;
 
 
 // logging
@@ -61,27 +90,100 @@ use log::LevelFilter;
 
 pub struct Application{
     // state: ApplicationState,
-    render_manager: RenderManager,
+    // render manager, event loop and egui state are all None when running headless
+    render_manager: Option<RenderManager>,
     scene_manager: SceneManager,
     input_manager: InputManager,
     plugin_manager: PluginManager,
     event_loop: Option<EventLoop<()>>,
-    state: Box<dyn ApplicationState>,
-    egui_winit_state: egui_winit::State,
+    state_stack: ApplicationStateStack,
+    egui_winit_state: Option<egui_winit::State>,
 
     start_instant: Instant,
+    frame_count: u64,
+    max_frames: Option<u64>,
+    screenshot_path: Option<PathBuf>,
+    fixed_timestep: FixedTimestep,
+    // copy of the active scene's clock so it carries over when scenes are swapped
+    time: Time,
+    // systems and stages added from outside the engine. every scene gets a copy
+    schedules: ScheduleRegistry,
+    // how to upgrade scenes saved with older components. every scene gets a copy
+    migrations: MigrationRegistry,
+    // engine and plugin types. shared by every scene, so types registered later reach them all
+    type_registry: TypeRegistryArc,
+    // projects opened in the editor. headless runs don't read or add to the user's list
+    recent_projects: RecentProjects,
 }
 
 impl Application{
 
     // startup process
     pub fn create_application(log_level: LevelFilter) -> Self {
-        SimpleLogger::new().with_level(log_level).init().unwrap();
+        Self::create_application_with_config(ApplicationConfig::default().with_log_level(log_level))
+    }
+
+    // startup process without a window, surface or swapchain. render systems are skipped
+    // by their run criteria and the schedules are stepped manually with run_headless
+    pub fn create_headless_application(log_level: LevelFilter) -> Self {
+        Self::create_application_with_config(
+            ApplicationConfig::default()
+                .with_log_level(log_level)
+                .headless(true)
+        )
+    }
+
+    pub fn create_application_with_config(config: ApplicationConfig) -> Self {
+        Self::init_logging(&config);
+
+        let mut app = if config.headless {
+            log::info!("Starting headless application ...");
+            Self::create(None, None, None, FixedTimestep::new(config.ticks_per_second))
+        } else {
+            log::info!("Starting application ...");
+            // create render manager. this is what owns the window
+            let (render_manager, event_loop) = RenderManager::new();
+
+            // get egui_winit state from render manager
+            let egui_winit_state = render_manager.create_egui_winit_state(&event_loop);
+
+            Self::create(
+                Some(render_manager),
+                Some(event_loop),
+                Some(egui_winit_state),
+                FixedTimestep::new(config.ticks_per_second)
+            )
+        };
+
+        app.max_frames = config.frames;
+        app.screenshot_path = config.screenshot.clone();
+
+        // queue up the startup project. the scene manager picks it up on the first update
+        if let Some(project) = config.project.as_ref() {
+            app.open_project(project.to_string_lossy().to_string(), config.scene.clone());
+        }
+
+        app
+    }
+
+    fn init_logging(config: &ApplicationConfig){
+        let mut logger = SimpleLogger::new().with_level(config.log_level);
+        for (module, level) in config.module_log_levels.iter() {
+            logger = logger.with_module_level(module, *level);
+        }
+        // ignore the error here. it just means a logger is already set, which happens when
+        // several headless applications are created in one process
+        let _ = logger.init();
         puffin::set_scopes_on(true);
+    }
 
-        log::info!("Starting application ...");
+    fn create(
+        render_manager: Option<RenderManager>,
+        event_loop: Option<EventLoop<()>>,
+        egui_winit_state: Option<egui_winit::State>,
+        fixed_timestep: FixedTimestep,
+    ) -> Self {
         // create other managers
-        let (mut render_manager, event_loop) = RenderManager::new();
         let mut scene_manager = SceneManager::new();
         let mut input_manager = InputManager::new();
         let mut plugin_manager = PluginManager::new();
@@ -91,24 +193,39 @@ impl Application{
         scene_manager.startup();
         input_manager.startup();
         plugin_manager.startup();
-        
-        // get egui_winit state from render manager
-        let egui_winit_state = render_manager.create_egui_winit_state(&event_loop);
 
         // set to idle state
         log::info!("Setting application idle state ...");
         scene_manager.create_and_set_staged_scene();
 
+        let recent_projects = if render_manager.is_some() {
+            RecentProjects::load()
+        } else {
+            RecentProjects::default()
+        };
+
         let mut app = Self{
             render_manager,
             scene_manager,
             input_manager,
             plugin_manager,
-            event_loop: Some(event_loop),
-            state: Box::new(ApplicationIdleState::create()),
+            event_loop,
+            state_stack: ApplicationStateStack::new(Box::new(ApplicationIdleState::create())),
             egui_winit_state,
             start_instant: Instant::now(),
+            frame_count: 0,
+            max_frames: None,
+            screenshot_path: None,
+            fixed_timestep,
+            time: Time::new(fixed_timestep.step()),
+            schedules: ScheduleRegistry::new(),
+            migrations: MigrationRegistry::new(),
+            type_registry: TypeRegistryResource::default().0,
+            recent_projects,
         };
+        app.plugin_manager.register_types(&mut app.type_registry.write());
+        app.plugin_manager.register_schedules(&mut app.schedules);
+        app.plugin_manager.register_migrations(&mut app.migrations);
 
         // prep staged scene
         log::info!("Prepping and activating idle scene ...");
@@ -121,11 +238,99 @@ impl Application{
 
     }
 
+    pub fn is_headless(&self) -> bool {
+        self.render_manager.is_none()
+    }
+
+    // asks the scene manager to open a project. this happens on the next manager update. without
+    // a scene name the project's default scene is opened
+    pub fn open_project(&mut self, project_path: String, scene_name: Option<String>){
+        log::info!("Queueing project {} with scene {:?} to open...", project_path, scene_name);
+        let mut scene = match self.scene_manager.get_active_scene() {
+            Some(s) => s,
+            None => {
+                log::error!("No active scene to send open project message to.");
+                return;
+            }
+        };
+        let mut world = scene.get_world().unwrap();
+        match world.get_resource_mut::<SceneManagerMessagePump>() {
+            Some(mut pump) => pump.send(SceneManagerMessage::OpenProject{
+                path: project_path,
+                scene_name,
+            }),
+            None => log::error!("{}", EngineError::MissingResource("SceneManagerMessagePump")),
+        }
+    }
+
+    // logs the error and sends it to the active scene so the ui can show it
+    fn report_error(&mut self, error: EngineError){
+        log::error!("{}", error);
+        if let Some(mut scene) = self.scene_manager.get_active_scene() {
+            if let Some(mut world) = scene.get_world() {
+                world.send_event(ErrorEvent(error));
+            }
+        }
+    }
+
+    // puts the project at the top of the recent projects and saves the list. only the editor
+    // keeps a list
+    fn record_recent_project(&mut self, project: &CurrentProject){
+        if self.is_headless() {
+            return;
+        }
+        self.recent_projects.record(&project.path, &project.manifest.name);
+        // not worth stopping the project for
+        if let Err(e) = self.recent_projects.save() {
+            log::warn!("{}", e);
+        }
+    }
+
+    // once a project's scene is in, leave the idle state. there's no editor without a window,
+    // so headless projects just run
+    fn enter_project_state(&mut self){
+        if self.state_stack.top().state_type() != ApplicationStateType::Idle {
+            return;
+        }
+        let state_type = if self.render_manager.is_some() {
+            ApplicationStateType::Editor
+        } else {
+            ApplicationStateType::Game
+        };
+        let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+        self.state_stack.replace(create_application_state(state_type), &mut active_scene);
+    }
+
+    // takes the loading screen down if it's up
+    fn pop_loading_state(&mut self){
+        if self.state_stack.top().state_type() == ApplicationStateType::Loading {
+            let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+            self.state_stack.pop(&mut active_scene);
+            active_scene.insert_resource(self.state_stack.types());
+        }
+    }
+
+    // spawns a scene file into the active scene without replacing what's there
+    pub fn load_scene_additive(&mut self, scene_path: &str) -> Result<SceneHandle, EngineError> {
+        self.scene_manager.load_scene_additive(scene_path)
+    }
+
+    // despawns everything a scene loaded with load_scene_additive spawned
+    pub fn unload_scene(&mut self, handle: SceneHandle) -> Result<(), EngineError> {
+        self.scene_manager.unload_scene(handle)
+    }
+
+    pub fn loaded_scenes(&self) -> &Vec<(SceneHandle, String)> {
+        self.scene_manager.loaded_scenes()
+    }
+
     // Shutdown process
     fn shutdown(&mut self){
         log::info!("Shutting down application...");
         self.scene_manager.shutdown();
-        self.render_manager.shutdown();
+        if let Some(render_manager) = self.render_manager.as_mut() {
+            render_manager.shutdown();
+        }
         self.input_manager.shutdown();
     }
 
@@ -137,26 +342,188 @@ impl Application{
             let mut _scene = self.scene_manager.get_staged_scene().unwrap();
             let scene = _scene.deref_mut();
 
-            let state: &(dyn ApplicationState) = self.state.borrow();
-            state.overlay_interface_on_staged_scene(scene.borrow_mut());
+            // the idle interface starts from the recent projects
+            scene.insert_resource(self.recent_projects.clone());
+            self.state_stack.overlay_interface_on_staged_scene(scene.borrow_mut());
             self.input_manager.prep_staged_scene(scene.borrow_mut());
-            self.render_manager.prep_staged_scene(scene.borrow_mut());
+            if let Some(render_manager) = self.render_manager.as_mut() {
+                render_manager.prep_staged_scene(scene.borrow_mut());
+            }
+            scene.insert_resource(self.fixed_timestep);
+            scene.insert_resource(Interpolation::default());
+            scene.insert_resource(self.time.clone());
+            scene.insert_resource(self.state_stack.types());
+            scene.insert_resource(self.play_mode());
+            scene.insert_resource(self.schedules.clone());
+            scene.insert_resource(self.migrations.clone());
+            scene.insert_resource(TypeRegistryResource(self.type_registry.clone()));
+        }
+    }
+
+    // adds systems and stages to the scene schedules. they go into the active scene right away
+    // (except for setup, which has already run) and into every scene activated after this
+    pub fn register_schedules<F: FnOnce(&mut ScheduleRegistry)>(&mut self, f: F){
+        let start = self.schedules.len();
+        f(&mut self.schedules);
+        self.apply_registrations_since(start);
+    }
+
+    // adds scene migrations. they're used from the next scene load on
+    pub fn register_migrations<F: FnOnce(&mut MigrationRegistry)>(&mut self, f: F){
+        f(&mut self.migrations);
+        self.share_migrations();
+    }
+
+    // registers reflected types with every scene, the active one included
+    pub fn register_types<F: FnOnce(&mut TypeRegistry)>(&mut self, f: F){
+        f(&mut self.type_registry.write());
+    }
+
+    fn share_migrations(&mut self){
+        if let Some(mut active_scene) = self.scene_manager.get_active_scene() {
+            active_scene.insert_resource(self.migrations.clone());
+        }
+    }
+
+    // loads a plugin library and adds its types, systems and migrations
+    pub unsafe fn load_plugin(&mut self, path: &str) -> Result<(), EngineError> {
+        if let Err(e) = self.plugin_manager.load_plugin(path) {
+            let error = EngineError::PluginLoad{
+                path: String::from(path),
+                reason: e.to_string(),
+            };
+            self.report_error(error.clone());
+            return Err(error);
+        }
+        self.register_last_plugin();
+        Ok(())
+    }
+
+    // adds a plugin compiled into the application, along with its types, systems and migrations
+    pub fn add_plugin(&mut self, plugin: Box<dyn Plugin>){
+        self.plugin_manager.add_plugin(plugin);
+        self.register_last_plugin();
+    }
+
+    fn register_last_plugin(&mut self){
+        self.plugin_manager.register_last_plugin_types(&mut self.type_registry.write());
+        let start = self.schedules.len();
+        self.plugin_manager.register_last_plugin_schedules(&mut self.schedules);
+        self.apply_registrations_since(start);
+        self.plugin_manager.register_last_plugin_migrations(&mut self.migrations);
+        self.share_migrations();
+    }
+
+    fn apply_registrations_since(&mut self, start: usize){
+        let mut active_scene = match self.scene_manager.get_active_scene() {
+            Some(s) => s,
+            None => return,
+        };
+        for schedule in [SceneSchedule::Update, SceneSchedule::Render, SceneSchedule::Teardown] {
+            if let Some(target) = active_scene.schedule_mut(schedule) {
+                self.schedules.apply_since(start, schedule, target);
+            }
+        }
+        active_scene.insert_resource(self.schedules.clone());
+    }
+
+    // drains the state events sent during the last frame and applies them to the state stack
+    fn process_state_events(&mut self){
+        let mut active_scene = match self.scene_manager.get_active_scene() {
+            Some(s) => s,
+            None => return,
+        };
+        let state_events: Vec<ApplicationStateEvent> = {
+            let mut world = active_scene.get_world().unwrap();
+            match world.get_resource_mut::<Events<ApplicationStateEvent>>() {
+                Some(mut events) => events.drain().collect(),
+                None => return,
+            }
+        };
+        for event in state_events.into_iter() {
+            match event {
+                ApplicationStateEvent::Push(state_type) => {
+                    self.state_stack.push(create_application_state(state_type), &mut active_scene);
+                },
+                ApplicationStateEvent::Pop => {
+                    self.state_stack.pop(&mut active_scene);
+                },
+                ApplicationStateEvent::Replace(state_type) => {
+                    self.state_stack.replace(create_application_state(state_type), &mut active_scene);
+                },
+            }
         }
+        active_scene.insert_resource(self.state_stack.types());
+    }
+
+    // drains the editor's play control events. play and stop push and pop the play state,
+    // pause and resume go through the scene's clock
+    fn process_play_mode_events(&mut self){
+        let play_mode_events: Vec<PlayModeEvent> = {
+            let mut active_scene = match self.scene_manager.get_active_scene() {
+                Some(s) => s,
+                None => return,
+            };
+            let mut world = active_scene.get_world().unwrap();
+            match world.get_resource_mut::<Events<PlayModeEvent>>() {
+                Some(mut events) => events.drain().collect(),
+                None => return,
+            }
+        };
+
+        for event in play_mode_events.into_iter() {
+            let play_mode = self.play_mode();
+            match (event, play_mode) {
+                (PlayModeEvent::Play, PlayMode::Editing) => {
+                    let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+                    self.state_stack.push(create_application_state(ApplicationStateType::Play), &mut active_scene);
+                },
+                (PlayModeEvent::Stop, PlayMode::Playing) | (PlayModeEvent::Stop, PlayMode::Paused) => {
+                    let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+                    self.state_stack.pop(&mut active_scene);
+                },
+                (PlayModeEvent::Pause, PlayMode::Playing) => self.with_time(|time| time.pause()),
+                (PlayModeEvent::Resume, PlayMode::Paused) => self.with_time(|time| time.resume()),
+                (PlayModeEvent::Step, PlayMode::Paused) => self.step_fixed_update(),
+                (event, play_mode) => log::warn!("Ignoring {:?} while {:?}", event, play_mode),
+            }
+        }
+
+        let play_mode = self.play_mode();
+        let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+        active_scene.insert_resource(self.state_stack.types());
+        active_scene.insert_resource(play_mode);
+    }
+
+    pub fn play_mode(&self) -> PlayMode {
+        if !self.state_stack.contains(ApplicationStateType::Editor) {
+            PlayMode::Running
+        } else if !self.state_stack.contains(ApplicationStateType::Play) {
+            PlayMode::Editing
+        } else if self.time.is_paused() {
+            PlayMode::Paused
+        } else {
+            PlayMode::Playing
+        }
+    }
+
+    // runs the visible states' schedules on the active scene
+    fn run_state_schedules(&mut self){
+        let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+        self.state_stack.run_schedules(&mut active_scene);
     }
 
     // main game loop
     pub fn run(mut self) {
         log::info!("Running the application...");
-        let event_loop = self.event_loop.take().unwrap();
+        let event_loop = self.event_loop.take().expect("No event loop. Use run_headless for headless applications.");
 
         // overwrite time
         log::info!("Startup time: {:?}", Instant::now().duration_since(self.start_instant));
 
         self.start_instant = Instant::now();
-        let ticks_per_second = 25;
-        let skip_ticks = 1000 / ticks_per_second; // tick every 40 ms
-        let max_frame_skip = 5;
-        let _interpolation: f32 = 0.0;
+        let skip_ticks = self.fixed_timestep.step(); // 40 ms at the default 25 ticks per second
+        let max_frame_skip = self.fixed_timestep.max_frame_skip;
         let mut next_tick = Instant::now();
 
         event_loop.run(move |event, _, control_flow| {
@@ -168,7 +535,19 @@ impl Application{
             // if should render, do that
             if should_render {
                 puffin::GlobalProfiler::lock().new_frame();
+                self.with_time(|time| time.update_frame(Instant::now()));
+                let interpolation = Self::interpolation_alpha(Instant::now(), next_tick, skip_ticks);
+                self.set_interpolation(interpolation);
+                self.queue_screenshot();
+                self.run_state_schedules();
                 self.render_scene();
+                self.frame_count += 1;
+                if let Some(max_frames) = self.max_frames {
+                    if self.frame_count >= max_frames {
+                        log::info!("Rendered {} frames. Exiting...", self.frame_count);
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
             }
 
             // do physics / non-render updates
@@ -178,11 +557,10 @@ impl Application{
                 self.update_managers();
 
                 // run physics
-                let mut active_scene = self.scene_manager.get_active_scene().unwrap();
-                active_scene.run_update_schedule();
+                self.run_fixed_update();
 
                 //
-                next_tick.add_assign(Duration::from_millis(skip_ticks));
+                next_tick.add_assign(skip_ticks);
                 loops = loops + 1;
             }
 
@@ -190,17 +568,150 @@ impl Application{
         }); // end of event_loop run
     } // end of run function
 
+    // runs one fixed tick of the update schedule unless the clock is paused or the editor
+    // isn't playing
+    fn run_fixed_update(&mut self){
+        if self.time.is_paused() || !self.play_mode().is_simulating() {
+            return;
+        }
+        self.step_fixed_update();
+    }
+
+    // runs one fixed tick no matter what. used to single step while paused
+    fn step_fixed_update(&mut self){
+        self.with_time(|time| time.advance_tick());
+        let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+        active_scene.run_update_schedule();
+    }
+
+    // applies f to the active scene's Time resource and keeps a copy of the result
+    fn with_time<F: FnOnce(&mut Time)>(&mut self, f: F){
+        let fallback = self.time.clone();
+        let time = {
+            let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+            let mut world = active_scene.get_world().unwrap();
+            let mut time = world.get_resource_or_insert_with(|| fallback);
+            f(&mut time);
+            time.clone()
+        };
+        self.time = time;
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    // how far the frame is between the previous tick and next_tick
+    fn interpolation_alpha(now: Instant, next_tick: Instant, step: Duration) -> f32 {
+        let until_next_tick = next_tick.saturating_duration_since(now);
+        1.0 - until_next_tick.as_secs_f32() / step.as_secs_f32()
+    }
+
+    fn set_interpolation(&mut self, alpha: f32){
+        let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+        active_scene.insert_resource(Interpolation::new(alpha));
+    }
+
+    pub fn fixed_timestep(&self) -> FixedTimestep {
+        self.fixed_timestep
+    }
+
+    // the registry every scene shares
+    pub fn type_registry(&self) -> &TypeRegistryArc {
+        &self.type_registry
+    }
+
+    // steps the application for a fixed number of ticks without touching winit. each tick runs
+    // the manager updates, the update schedule and the render schedule on the active scene
+    pub fn run_headless(&mut self, ticks: u64) {
+        log::info!("Running the application headless for {} ticks...", ticks);
+        for _ in 0..ticks {
+            self.update_managers();
+
+            // every headless frame lines up exactly with a tick
+            let step = self.fixed_timestep.step();
+            self.with_time(|time| time.advance_frame(step));
+            self.set_interpolation(0.0);
+
+            self.run_fixed_update();
+            self.run_state_schedules();
+            let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+            active_scene.run_render_schedule();
+            self.frame_count += 1;
+        }
+    }
+
+    pub fn get_active_scene(&self) -> Option<RefMut<Scene<Active>>> {
+        self.scene_manager.get_active_scene()
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    // the screenshot is taken on the last frame, or the first one if there is no frame limit
+    fn queue_screenshot(&mut self){
+        let screenshot_frame = self.max_frames.unwrap_or(1);
+        if self.frame_count + 1 != screenshot_frame {
+            return;
+        }
+        if let (Some(path), Some(render_manager)) = (self.screenshot_path.take(), self.render_manager.as_mut()) {
+            render_manager.request_screenshot(path);
+        }
+    }
+
     fn update_managers(&mut self){
+        // state changes first so a state can react before the scene manager swaps scenes
+        self.process_state_events();
+        self.process_play_mode_events();
+
+        // a failed load leaves the current scene running
         let scene_manager_update_result = {
             match self.scene_manager.update(){
                 Ok(r) => r,
-                Err(e) => panic!("{:?}", e)
+                Err(e) => {
+                    self.pop_loading_state();
+                    self.report_error(e);
+                    SceneManagerUpdateResults::NoUpdate
+                }
             }
         };
         match scene_manager_update_result {
-            SceneManagerUpdateResults::NewSceneOpened => {
-                self.prep_staged_scene();
-                self.activate_staged_scene();
+            SceneManagerUpdateResults::SceneLoadStarted => {
+                // the loading screen sits on top of whatever was running until the new scene is in
+                if self.state_stack.top().state_type() != ApplicationStateType::Loading {
+                    let mut active_scene = self.scene_manager.get_active_scene().unwrap();
+                    self.state_stack.push(create_application_state(ApplicationStateType::Loading), &mut active_scene);
+                    active_scene.insert_resource(self.state_stack.types());
+                }
+            },
+            SceneManagerUpdateResults::ProjectPluginsNeeded(plugins) => {
+                for plugin in plugins.iter().filter(|p| !self.plugin_manager.is_loaded(p)) {
+                    log::info!("Loading project plugin {}...", plugin);
+                    // errors are reported by load_plugin. the project opens without the plugin
+                    let _ = unsafe { self.load_plugin(plugin) };
+                }
+            },
+            SceneManagerUpdateResults::SceneLoadFinished => {
+                self.pop_loading_state();
+                match self.scene_manager.build_loaded_scene() {
+                    Ok(mut staged_scene) => {
+                        let project = staged_scene
+                            .get_world()
+                            .and_then(|world| world.get_resource::<CurrentProject>().cloned());
+                        if let (Some(project), Some(render_manager)) = (project.as_ref(), self.render_manager.as_mut()) {
+                            render_manager.apply_window_settings(&project.manifest.window);
+                        }
+                        if let Some(project) = project.as_ref() {
+                            self.record_recent_project(project);
+                        }
+                        self.enter_project_state();
+                        self.scene_manager.replace_active_scene(staged_scene);
+                        self.prep_staged_scene();
+                        self.activate_staged_scene();
+                    },
+                    Err(e) => self.report_error(e),
+                }
             },
             SceneManagerUpdateResults::NoUpdate => log::debug!("No action required from scene manager"),
         }
@@ -210,7 +721,9 @@ impl Application{
         
         // run input
         self.input_manager.update(active_scene.borrow_mut());
-        self.render_manager.update(active_scene.borrow_mut());
+        if let Some(render_manager) = self.render_manager.as_mut() {
+            render_manager.update(active_scene.borrow_mut());
+        }
     }
 
     fn handle_winit_event(
@@ -226,7 +739,10 @@ impl Application{
                     let egui_ctx = {
                         world.get_resource_mut::<EguiState>().expect("Couldn't get Egui state from world").ctx.clone()
                     };
-                    let event_response = self.egui_winit_state.on_event(&egui_ctx, &event);
+                    let event_response = self.egui_winit_state
+                        .as_mut()
+                        .expect("No egui winit state on windowed application")
+                        .on_event(&egui_ctx, &event);
                     event_response    
                 };
                 if !event_response.consumed {
@@ -259,7 +775,9 @@ impl Application{
             // window resized
             WindowEvent::Resized(_) => {
                 log::debug!("Window resized...");
-                self.render_manager.recreate_swapchain();
+                if let Some(render_manager) = self.render_manager.as_mut() {
+                    render_manager.recreate_swapchain();
+                }
                 log::info!("Swapchain Recreated...");
             },
 
@@ -276,6 +794,18 @@ impl Application{
                     self.input_manager.handle_key_input(Some(virtual_code.clone()));
             },
 
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        virtual_keycode: Some(virtual_code),
+                        state: ElementState::Released,
+                        ..
+                    },
+                ..
+                } => {
+                    self.input_manager.handle_key_release(virtual_code.clone());
+            },
+
             WindowEvent::CursorEntered { device_id } => {
                 log::debug!("Cursor entered window: todo")
             },
@@ -289,6 +819,8 @@ impl Application{
                     log::info!("Window Gained Focus");
                 } else {
                     log::info!("Window Lost Focus");
+                    // we won't see the key releases anymore
+                    self.input_manager.clear_held_keys();
                 }
             }
 
@@ -329,10 +861,13 @@ impl Application{
 
     fn render_scene(&mut self){
         let mut current_scene = self.scene_manager.get_active_scene().unwrap();
-        let mut egui_winit_state = &mut self.egui_winit_state;
-        self.render_manager.draw(
+        let (render_manager, egui_winit_state) = match (self.render_manager.as_mut(), self.egui_winit_state.as_mut()) {
+            (Some(r), Some(e)) => (r, e),
+            _ => return,
+        };
+        render_manager.draw(
             &mut current_scene,
-            &mut egui_winit_state
+            egui_winit_state
         );
     }
 
@@ -1,4 +1,3 @@
-use crate::core::events::terrain_events::TerrainRecalculateEvent;
 use crate::core::scene::{
     Scene,
     Active,
@@ -7,44 +6,101 @@ use crate::core::scene::{
 
 use bevy_ecs::{
     schedule::Stage,
+    entity::Entity,
+    query::With,
 };
-use bevy_ecs::prelude::Schedule;
-use bevy_hierarchy::AddChild;
-use ember_math::{Vector4f, Vector3f, Matrix4f};
+use bevy_ecs::prelude::{Schedule, SystemStage, Res, EventWriter};
+use ember_math::{Vector4f, Vector3f};
+use winit::event::VirtualKeyCode;
 
 
 
 
 use crate::core::plugins::components::{
-    DebugUiComponent,
     CameraComponent,
     InputComponent,
-    FileSubMenuComponent,
-    SceneGraphComponent,
     TransformComponent, TerrainComponent, RenderableComponent, GeometryComponent, GeometryType, TransformUiComponent, DirectionalLightComponent, AmbientLightingComponent, TransformBuilder,
 };
-use crate::core::events::project_events::SaveEvent;
-use crate::core::events::project_events::CreateProjectEvent;
-use crate::core::events::project_events::CloseProjectEvent;
-use crate::core::events::project_events::OpenProjectEvent;
-use crate::core::events::menu_messages::MenuMessage;
+use crate::core::plugins::components::ui::main_menu_component::EditorUiState;
+use crate::core::events::error_events::ErrorEvent;
+use crate::core::errors::EngineError;
+use crate::core::events::application_state_events::{
+    ApplicationStateEvent,
+    ApplicationStateType,
+};
+use crate::core::managers::input_manager::KeyInputQueue;
 
-use crate::core::managers::SceneManagerMessagePump;
-use crate::core::plugins::components::AppInterfaceFlag;
+use crate::core::systems::{
+    spawn_main_menu_interface,
+    spawn_editor_interface,
+    spawn_loading_interface,
+    despawn_interface,
+};
 
-use bevy_ecs::event::Events;
-use crate::core::scene::TypeRegistryResource;
+use crate::core::scene::SceneSnapshot;
+use crate::core::application::Time;
 
 pub trait ApplicationState{
+    fn state_type(&self) -> ApplicationStateType;
     fn run_schedule(&mut self, scene: &mut Scene<Active>);
     fn init_schedule(&mut self);
     fn scene_interface_path(&self) -> &'static str;
-    fn overlay_interface_on_staged_scene(&self, scene: &mut Scene<Staged>);
+    // called on every newly staged scene while this state is visible. the initial state
+    // only ever gets this, not on_enter
+    fn overlay_interface_on_staged_scene(&mut self, scene: &mut Scene<Staged>);
+
+    // called when the state is pushed onto a running scene
+    fn on_enter(&mut self, _scene: &mut Scene<Active>){}
+    // called when the state is popped or replaced
+    fn on_exit(&mut self, _scene: &mut Scene<Active>){}
+    // called when a non overlay state is pushed on top of this one
+    fn on_pause(&mut self, _scene: &mut Scene<Active>){}
+    // called when the state on top of this one is popped
+    fn on_resume(&mut self, _scene: &mut Scene<Active>){}
+    // overlay states draw on top of the states below them instead of pausing them
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub fn create_application_state(state_type: ApplicationStateType) -> Box<dyn ApplicationState> {
+    match state_type {
+        ApplicationStateType::Idle => Box::new(ApplicationIdleState::create()),
+        ApplicationStateType::Editor => Box::new(ApplicationEditorState::create()),
+        ApplicationStateType::Game => Box::new(ApplicationGameState::create()),
+        ApplicationStateType::Loading => Box::new(ApplicationLoadingState::create()),
+        ApplicationStateType::Play => Box::new(ApplicationPlayState::create()),
+    }
+}
+
+// keeps track of the interface root a state spawned so it can take it back down
+#[derive(Default)]
+pub struct StateInterface{
+    root: Option<Entity>,
+}
+
+impl StateInterface{
+    pub fn spawn<F>(&mut self, scene_world: &mut bevy_ecs::world::World, spawn_fn: F)
+    where
+        F: FnOnce(&mut bevy_ecs::world::World) -> Entity
+    {
+        if let Some(root) = self.root.take() {
+            despawn_interface(scene_world, root);
+        }
+        self.root = Some(spawn_fn(scene_world));
+    }
+
+    pub fn despawn(&mut self, scene_world: &mut bevy_ecs::world::World){
+        if let Some(root) = self.root.take() {
+            despawn_interface(scene_world, root);
+        }
+    }
 }
 
 pub struct ApplicationIdleState{
     pub schedule: Option<Box<dyn Stage>>,
     pub scene_interface_path: &'static str,
+    pub interface: StateInterface,
 }
 
 impl ApplicationIdleState{
@@ -52,14 +108,19 @@ impl ApplicationIdleState{
         ApplicationIdleState{
             schedule: None,
             scene_interface_path: "./idle_state.ron",
+            interface: StateInterface::default(),
         }
     }
 }
 
 impl ApplicationState for ApplicationIdleState {
+    fn state_type(&self) -> ApplicationStateType {
+        ApplicationStateType::Idle
+    }
+
     fn run_schedule(&mut self, scene: &mut Scene<Active>){
-        log::info!("Running scene schedule...");
-        let mut schedule = self.schedule.take().expect("No setup schedule");
+        log::debug!("Running idle state schedule...");
+        let mut schedule = self.schedule.take().expect("No idle state schedule");
         schedule.run(&mut *scene.get_world().unwrap());
         self.schedule = Some(schedule);
     }
@@ -73,63 +134,8 @@ impl ApplicationState for ApplicationIdleState {
         self.scene_interface_path
     }
 
-    fn overlay_interface_on_staged_scene(&self, scene: &mut Scene<Staged>){
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<SaveEvent>>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<TypeRegistryResource>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<SceneManagerMessagePump>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<CreateProjectEvent>>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<CloseProjectEvent>>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<OpenProjectEvent>>();
-
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<MenuMessage<FileSubMenuComponent>>>();
-        scene.get_world()
-            .unwrap()
-            .init_resource::<Events<TerrainRecalculateEvent>>();
-        {
-            let mut world = scene.get_world().unwrap();
-            let registry_arc = &world.get_resource_mut::<TypeRegistryResource>().unwrap().0;
-            let mut registry = registry_arc.write();
-            registry.register::<AppInterfaceFlag>();
-            registry.register::<DebugUiComponent>();
-            registry.register::<FileSubMenuComponent>();
-            registry.register::<CameraComponent>();
-            registry.register::<InputComponent>();
-            registry.register::<TransformComponent>();
-            registry.register::<TransformUiComponent>();
-            registry.register::<Vector3f>();
-            registry.register::<Vector4f>();
-            registry.register::<Matrix4f>();
-
-
-            // should these be elsewhere?
-            registry.register::<RenderableComponent>();
-            registry.register::<GeometryComponent>();
-            registry.register::<DirectionalLightComponent>();
-
-            registry.register::<TerrainComponent>();
-            registry.register::<crate::core::plugins::components::TerrainUiComponent>();
-            registry.register::<crate::core::plugins::components::GeometryType>();
-            registry.register::<crate::core::plugins::components::AmbientLightingComponent>();
-        }
+    fn overlay_interface_on_staged_scene(&mut self, scene: &mut Scene<Staged>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_main_menu_interface);
 
         scene.get_world()
             .unwrap()
@@ -199,11 +205,289 @@ impl ApplicationState for ApplicationIdleState {
             .insert(box_transform_z)
             .insert(TransformUiComponent{});
     }
-    
+
+    fn on_enter(&mut self, scene: &mut Scene<Active>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_main_menu_interface);
+    }
+
+    fn on_exit(&mut self, scene: &mut Scene<Active>){
+        self.interface.despawn(&mut scene.get_world().unwrap());
+    }
+
+    fn on_pause(&mut self, scene: &mut Scene<Active>){
+        self.interface.despawn(&mut scene.get_world().unwrap());
+    }
+
+    fn on_resume(&mut self, scene: &mut Scene<Active>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_main_menu_interface);
+    }
 }
 
+// editing an open project. owns the full editor interface and makes sure there is a camera
 pub struct ApplicationEditorState{
     pub schedule: Option<Box<dyn Stage>>,
     pub scene_interface_path: &'static str,
+    pub interface: StateInterface,
+}
+
+impl ApplicationEditorState{
+    pub fn create() -> Self{
+        ApplicationEditorState{
+            schedule: None,
+            scene_interface_path: "./editor_state.ron",
+            interface: StateInterface::default(),
+        }
+    }
+
+    fn spawn_editor_camera_if_missing(world: &mut bevy_ecs::world::World){
+        let mut cameras = world.query_filtered::<Entity, With<CameraComponent>>();
+        if cameras.iter(world).next().is_none() {
+            log::info!("No camera in scene. Adding editor camera...");
+            world
+                .spawn_empty()
+                .insert(CameraComponent::default())
+                .insert(TransformComponent::create_empty())
+                .insert(InputComponent::create());
+        }
+    }
+}
+
+impl ApplicationState for ApplicationEditorState {
+    fn state_type(&self) -> ApplicationStateType {
+        ApplicationStateType::Editor
+    }
+
+    fn run_schedule(&mut self, scene: &mut Scene<Active>){
+        log::debug!("Running editor state schedule...");
+        let mut schedule = self.schedule.take().expect("No editor state schedule");
+        schedule.run(&mut *scene.get_world().unwrap());
+        self.schedule = Some(schedule);
+    }
+
+    fn init_schedule(&mut self){
+        let schedule = Schedule::default();
+        self.schedule = Some(Box::new(schedule));
+    }
+
+    fn scene_interface_path(&self) -> &'static str{
+        self.scene_interface_path
+    }
+
+    fn overlay_interface_on_staged_scene(&mut self, scene: &mut Scene<Staged>){
+        let mut world = scene.get_world().unwrap();
+        Self::spawn_editor_camera_if_missing(&mut world);
+        self.interface.spawn(&mut world, spawn_editor_interface);
+    }
+
+    fn on_enter(&mut self, scene: &mut Scene<Active>){
+        let mut world = scene.get_world().unwrap();
+        Self::spawn_editor_camera_if_missing(&mut world);
+        self.interface.spawn(&mut world, spawn_editor_interface);
+    }
+
+    fn on_exit(&mut self, scene: &mut Scene<Active>){
+        self.interface.despawn(&mut scene.get_world().unwrap());
+    }
+
+    fn on_pause(&mut self, scene: &mut Scene<Active>){
+        self.interface.despawn(&mut scene.get_world().unwrap());
+    }
+
+    fn on_resume(&mut self, scene: &mut Scene<Active>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_editor_interface);
+    }
+}
+
+// pops the game state when escape is pressed
+pub fn ExitGameStateSystem(
+    input: Res<KeyInputQueue>,
+    mut state_events: EventWriter<ApplicationStateEvent>,
+){
+    if input.queue.contains(&VirtualKeyCode::Escape) {
+        state_events.send(ApplicationStateEvent::Pop);
+    }
+}
+
+// running the scene as a game. no editor interface
+pub struct ApplicationGameState{
+    pub schedule: Option<Box<dyn Stage>>,
+    pub scene_interface_path: &'static str,
+}
+
+impl ApplicationGameState{
+    pub fn create() -> Self{
+        ApplicationGameState{
+            schedule: None,
+            scene_interface_path: "./game_state.ron",
+        }
+    }
+}
+
+impl ApplicationState for ApplicationGameState {
+    fn state_type(&self) -> ApplicationStateType {
+        ApplicationStateType::Game
+    }
+
+    fn run_schedule(&mut self, scene: &mut Scene<Active>){
+        log::debug!("Running game state schedule...");
+        let mut schedule = self.schedule.take().expect("No game state schedule");
+        schedule.run(&mut *scene.get_world().unwrap());
+        self.schedule = Some(schedule);
+    }
+
+    fn init_schedule(&mut self){
+        let mut schedule = Schedule::default();
+        schedule.add_stage("state_input", SystemStage::parallel()
+            .with_system(ExitGameStateSystem)
+        );
+        self.schedule = Some(Box::new(schedule));
+    }
+
+    fn scene_interface_path(&self) -> &'static str{
+        self.scene_interface_path
+    }
+
+    fn overlay_interface_on_staged_scene(&mut self, _scene: &mut Scene<Staged>){
+    }
+}
+
+// drawn on top of whatever is running while a scene loads
+pub struct ApplicationLoadingState{
+    pub schedule: Option<Box<dyn Stage>>,
+    pub scene_interface_path: &'static str,
+    pub interface: StateInterface,
+}
+
+impl ApplicationLoadingState{
+    pub fn create() -> Self{
+        ApplicationLoadingState{
+            schedule: None,
+            scene_interface_path: "./loading_state.ron",
+            interface: StateInterface::default(),
+        }
+    }
+}
+
+impl ApplicationState for ApplicationLoadingState {
+    fn state_type(&self) -> ApplicationStateType {
+        ApplicationStateType::Loading
+    }
+
+    fn run_schedule(&mut self, scene: &mut Scene<Active>){
+        log::debug!("Running loading state schedule...");
+        let mut schedule = self.schedule.take().expect("No loading state schedule");
+        schedule.run(&mut *scene.get_world().unwrap());
+        self.schedule = Some(schedule);
+    }
+
+    fn init_schedule(&mut self){
+        let schedule = Schedule::default();
+        self.schedule = Some(Box::new(schedule));
+    }
+
+    fn scene_interface_path(&self) -> &'static str{
+        self.scene_interface_path
+    }
+
+    fn overlay_interface_on_staged_scene(&mut self, scene: &mut Scene<Staged>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_loading_interface);
+    }
+
+    fn on_enter(&mut self, scene: &mut Scene<Active>){
+        self.interface.spawn(&mut scene.get_world().unwrap(), spawn_loading_interface);
+    }
+
+    fn on_exit(&mut self, scene: &mut Scene<Active>){
+        self.interface.despawn(&mut scene.get_world().unwrap());
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+// play in editor. sits on top of the editor so its interface stays up, snapshots the scene
+// on enter and puts it back on exit
+pub struct ApplicationPlayState{
+    pub schedule: Option<Box<dyn Stage>>,
+    pub scene_interface_path: &'static str,
+    snapshot: Option<SceneSnapshot>,
+}
+
+impl ApplicationPlayState{
+    pub fn create() -> Self{
+        ApplicationPlayState{
+            schedule: None,
+            scene_interface_path: "./play_state.ron",
+            snapshot: None,
+        }
+    }
 }
 
+impl ApplicationState for ApplicationPlayState {
+    fn state_type(&self) -> ApplicationStateType {
+        ApplicationStateType::Play
+    }
+
+    fn run_schedule(&mut self, scene: &mut Scene<Active>){
+        log::debug!("Running play state schedule...");
+        let mut schedule = self.schedule.take().expect("No play state schedule");
+        schedule.run(&mut *scene.get_world().unwrap());
+        self.schedule = Some(schedule);
+    }
+
+    fn init_schedule(&mut self){
+        let mut schedule = Schedule::default();
+        schedule.add_stage("state_input", SystemStage::parallel()
+            .with_system(ExitGameStateSystem)
+        );
+        self.schedule = Some(Box::new(schedule));
+    }
+
+    fn scene_interface_path(&self) -> &'static str{
+        self.scene_interface_path
+    }
+
+    fn overlay_interface_on_staged_scene(&mut self, _scene: &mut Scene<Staged>){
+        // the snapshot belongs to the scene that was just closed
+        if self.snapshot.take().is_some() {
+            log::warn!("Scene changed while playing. The play snapshot has been dropped.");
+        }
+    }
+
+    fn on_enter(&mut self, scene: &mut Scene<Active>){
+        let snapshot = SceneSnapshot::capture(&scene.get_world().unwrap());
+        log::info!("Playing. Snapshot taken of {} entities.", snapshot.entity_count());
+        self.snapshot = Some(snapshot);
+    }
+
+    fn on_exit(&mut self, scene: &mut Scene<Active>){
+        if let Some(mut time) = scene.get_world().unwrap().get_resource_mut::<Time>() {
+            time.resume();
+        }
+        let snapshot = match self.snapshot.take() {
+            Some(s) => s,
+            None => return,
+        };
+        {
+            let mut world = scene.get_world().unwrap();
+            if let Err(e) = snapshot.restore(&mut world) {
+                let error = EngineError::SceneSpawn{
+                    path: String::from("play snapshot"),
+                    reason: e.to_string(),
+                };
+                log::error!("Couldn't restore the scene after playing: {}", error);
+                world.send_event(ErrorEvent(error));
+            }
+            // whatever was selected was despawned with the rest of the scene
+            world.resource_mut::<EditorUiState>().selected_entity = None;
+        }
+        // respawned entities need their geometry and renderables set up again
+        scene.run_setup_schedule();
+        log::info!("Stopped. Scene restored from snapshot.");
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
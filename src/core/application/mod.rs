@@ -1,6 +1,21 @@
 pub mod application_manager;
+pub mod application_config;
 pub mod profiler;
 pub mod application_states;
+pub mod state_stack;
+pub mod play_mode;
+pub mod time;
 
+pub use application_config::ApplicationConfig;
 pub use application_states::ApplicationIdleState;
-pub use application_states::ApplicationState;
\ No newline at end of file
+pub use application_states::ApplicationEditorState;
+pub use application_states::ApplicationGameState;
+pub use application_states::ApplicationLoadingState;
+pub use application_states::ApplicationPlayState;
+pub use application_states::ApplicationState;
+pub use state_stack::ApplicationStateStack;
+pub use state_stack::ActiveApplicationStates;
+pub use play_mode::PlayMode;
+pub use time::FixedTimestep;
+pub use time::Interpolation;
+pub use time::Time;
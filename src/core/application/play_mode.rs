@@ -0,0 +1,28 @@
+use bevy_ecs::prelude::Resource;
+
+// what the editor's play controls should show. kept up to date by the application
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayMode{
+    // not in the editor, the scene just runs
+    Running,
+    // in the editor with the simulation stopped. edits stick
+    Editing,
+    // in the editor running a snapshot of the scene. edits are thrown away on stop
+    Playing,
+    Paused,
+}
+
+impl Default for PlayMode{
+    fn default() -> Self {
+        PlayMode::Running
+    }
+}
+
+impl PlayMode{
+    pub fn is_simulating(&self) -> bool {
+        match self {
+            PlayMode::Running | PlayMode::Playing => true,
+            PlayMode::Editing | PlayMode::Paused => false,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use bevy_ecs::prelude::Resource;
+
+use crate::core::application::ApplicationState;
+use crate::core::events::application_state_events::ApplicationStateType;
+use crate::core::scene::{
+    Scene,
+    Active,
+    Staged,
+};
+
+// mirror of the state stack for systems that need to know what the application is doing.
+// bottom of the stack first
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ActiveApplicationStates(pub Vec<ApplicationStateType>);
+
+impl ActiveApplicationStates{
+    pub fn top(&self) -> Option<ApplicationStateType> {
+        self.0.last().copied()
+    }
+
+    pub fn contains(&self, state_type: ApplicationStateType) -> bool {
+        self.0.contains(&state_type)
+    }
+}
+
+// the application's states. the top state and any overlay states directly under it are visible,
+// everything below the first non overlay state is paused
+pub struct ApplicationStateStack{
+    states: Vec<Box<dyn ApplicationState>>,
+}
+
+impl ApplicationStateStack{
+    pub fn new(mut initial_state: Box<dyn ApplicationState>) -> Self {
+        initial_state.init_schedule();
+        ApplicationStateStack{
+            states: vec![initial_state],
+        }
+    }
+
+    pub fn top(&self) -> &dyn ApplicationState {
+        self.states.last().expect("Application state stack is empty").as_ref()
+    }
+
+    pub fn types(&self) -> ActiveApplicationStates {
+        ActiveApplicationStates(self.states.iter().map(|s| s.state_type()).collect())
+    }
+
+    pub fn contains(&self, state_type: ApplicationStateType) -> bool {
+        self.states.iter().any(|s| s.state_type() == state_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    // index of the lowest visible state
+    fn first_visible(&self) -> usize {
+        self.states.iter().rposition(|s| !s.is_overlay()).unwrap_or(0)
+    }
+
+    pub fn push(&mut self, mut state: Box<dyn ApplicationState>, scene: &mut Scene<Active>){
+        log::info!("Pushing application state {:?}...", state.state_type());
+        if !state.is_overlay() {
+            let first_visible = self.first_visible();
+            for covered in self.states[first_visible..].iter_mut().rev() {
+                covered.on_pause(scene);
+            }
+        }
+        state.init_schedule();
+        state.on_enter(scene);
+        self.states.push(state);
+    }
+
+    // the bottom state is never popped. returns false if nothing happened
+    pub fn pop(&mut self, scene: &mut Scene<Active>) -> bool {
+        if self.states.len() <= 1 {
+            log::warn!("Can't pop the last application state.");
+            return false;
+        }
+        let mut state = self.states.pop().unwrap();
+        log::info!("Popping application state {:?}...", state.state_type());
+        state.on_exit(scene);
+        if !state.is_overlay() {
+            let first_visible = self.first_visible();
+            for uncovered in self.states[first_visible..].iter_mut() {
+                uncovered.on_resume(scene);
+            }
+        }
+        true
+    }
+
+    // swaps the top state for another. the states underneath stay as they are unless the swap
+    // changes whether they can be seen
+    pub fn replace(&mut self, mut state: Box<dyn ApplicationState>, scene: &mut Scene<Active>){
+        let mut old_state = self.states.pop().expect("Application state stack is empty");
+        log::info!("Replacing application state {:?} with {:?}...", old_state.state_type(), state.state_type());
+        old_state.on_exit(scene);
+        let first_visible = self.first_visible();
+        match (old_state.is_overlay(), state.is_overlay()) {
+            // the new state covers what the overlay let through
+            (true, false) => {
+                for covered in self.states[first_visible..].iter_mut().rev() {
+                    covered.on_pause(scene);
+                }
+            },
+            // the overlay lets the states under it show
+            (false, true) => {
+                for uncovered in self.states[first_visible..].iter_mut() {
+                    uncovered.on_resume(scene);
+                }
+            },
+            _ => (),
+        }
+        state.init_schedule();
+        state.on_enter(scene);
+        self.states.push(state);
+    }
+
+    // lets every visible state put its interface on a newly staged scene, bottom first
+    pub fn overlay_interface_on_staged_scene(&mut self, scene: &mut Scene<Staged>){
+        let first_visible = self.first_visible();
+        for state in self.states[first_visible..].iter_mut() {
+            state.overlay_interface_on_staged_scene(scene);
+        }
+    }
+
+    // runs the schedules of every visible state, bottom first
+    pub fn run_schedules(&mut self, scene: &mut Scene<Active>){
+        let first_visible = self.first_visible();
+        for state in self.states[first_visible..].iter_mut() {
+            state.run_schedule(scene);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use bevy_ecs::prelude::Resource;
+use std::time::{Duration, Instant};
+
+// rate the update schedule runs at, independent of the frame rate
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct FixedTimestep{
+    pub ticks_per_second: u32,
+    // most update ticks to run back to back before rendering again
+    pub max_frame_skip: u32,
+}
+
+impl Default for FixedTimestep{
+    fn default() -> Self {
+        FixedTimestep{
+            ticks_per_second: 25,
+            max_frame_skip: 5,
+        }
+    }
+}
+
+impl FixedTimestep{
+    pub fn new(ticks_per_second: u32) -> Self {
+        FixedTimestep{
+            ticks_per_second: ticks_per_second.max(1),
+            ..Default::default()
+        }
+    }
+
+    pub fn step(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.ticks_per_second as f64)
+    }
+
+    pub fn step_seconds(&self) -> f32 {
+        1.0 / self.ticks_per_second as f32
+    }
+}
+
+// how far the current frame is between the last fixed tick and the next one. 0 is right on the
+// last tick, 1 is right on the next. render systems use this to blend simulation state
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Interpolation{
+    pub alpha: f32,
+}
+
+impl Interpolation{
+    pub fn new(alpha: f32) -> Self {
+        Interpolation{
+            alpha: alpha.max(0.0).min(1.0),
+        }
+    }
+
+    pub fn lerp(&self, previous: f32, current: f32) -> f32 {
+        previous + (current - previous) * self.alpha
+    }
+}
+
+// engine clock. inserted and advanced by the application once per frame and once per fixed tick.
+// systems can pause the clock or change its time scale through ResMut<Time>
+#[derive(Resource, Clone, Debug)]
+pub struct Time{
+    startup: Instant,
+    last_frame: Option<Instant>,
+    delta: Duration,
+    fixed_delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    tick_count: u64,
+    time_scale: f32,
+    paused: bool,
+}
+
+impl Default for Time{
+    fn default() -> Self {
+        Time::new(FixedTimestep::default().step())
+    }
+}
+
+impl Time{
+    pub fn new(fixed_delta: Duration) -> Self {
+        Time{
+            startup: Instant::now(),
+            last_frame: None,
+            delta: Duration::ZERO,
+            fixed_delta,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            tick_count: 0,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+
+    // advance the clock to now. the first frame has a delta of zero
+    pub fn update_frame(&mut self, now: Instant){
+        let delta = match self.last_frame {
+            Some(last_frame) => now.saturating_duration_since(last_frame),
+            None => Duration::ZERO,
+        };
+        self.last_frame = Some(now);
+        self.advance_frame(delta);
+    }
+
+    // advance the clock by a fixed amount. used when running headless so runs are repeatable
+    pub fn advance_frame(&mut self, delta: Duration){
+        self.delta = delta;
+        self.frame_count += 1;
+        if !self.paused {
+            self.elapsed += delta.mul_f32(self.time_scale);
+        }
+    }
+
+    pub fn advance_tick(&mut self){
+        self.tick_count += 1;
+    }
+
+    // frame delta with pause and time scale applied
+    pub fn delta_seconds(&self) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            self.delta.as_secs_f32() * self.time_scale
+        }
+    }
+
+    // frame delta ignoring pause and time scale. good for editor cameras and ui
+    pub fn raw_delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    // fixed tick delta with pause and time scale applied
+    pub fn fixed_delta_seconds(&self) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            self.fixed_delta.as_secs_f32() * self.time_scale
+        }
+    }
+
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    // scaled time the simulation has been running, excluding paused time
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    // wall clock time since the application started
+    pub fn since_startup(&self) -> Duration {
+        Instant::now().saturating_duration_since(self.startup)
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32){
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self){
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self){
+        self.paused = false;
+    }
+}
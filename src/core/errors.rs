@@ -0,0 +1,81 @@
+use std::string::String;
+use thiserror::Error;
+
+// everything in the scene and project pipeline that can fail without taking the engine down.
+// these get logged and sent to the ui as ErrorEvents
+#[derive(Debug, Clone, Error)]
+pub enum EngineError{
+    #[error("No active scene")]
+    NoActiveScene,
+    #[error("No world on current scene")]
+    NoWorldOnScene,
+    #[error("Resource {0} not found")]
+    MissingResource(&'static str),
+    #[error("Unknown scene manager message type")]
+    UnknownMessageType,
+    #[error("Couldn't read scene {path}: {reason}")]
+    SceneRead{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't deserialize scene {path}: {reason}")]
+    SceneDeserialization{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't spawn scene {path}: {reason}")]
+    SceneSpawn{
+        path: String,
+        reason: String,
+    },
+    #[error("No scene has finished loading")]
+    NoLoadedScene,
+    #[error("No loaded scene with handle {0}")]
+    NoSuchScene(u32),
+    #[error("Couldn't write scene {path}: {reason}")]
+    SceneWrite{
+        path: String,
+        reason: String,
+    },
+    #[error("'{0}' isn't a scene name. Scene names can't have a path in them")]
+    InvalidSceneName(String),
+    #[error("No project is open")]
+    NoProjectOpen,
+    #[error("Couldn't create project {path}: {reason}")]
+    ProjectCreation{
+        path: String,
+        reason: String,
+    },
+    #[error("Invalid project manifest {path}: {reason}")]
+    ProjectManifest{
+        path: String,
+        reason: String,
+    },
+    #[error("Prefab {path}: {reason}")]
+    Prefab{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't apply scene patch: {0}")]
+    ScenePatch(String),
+    #[error("Couldn't read or write height map {path}: {reason}")]
+    HeightMap{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't load mesh {path}: {reason}")]
+    Mesh{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't read or write recent projects {path}: {reason}")]
+    RecentProjects{
+        path: String,
+        reason: String,
+    },
+    #[error("Couldn't load plugin {path}: {reason}")]
+    PluginLoad{
+        path: String,
+        reason: String,
+    },
+}
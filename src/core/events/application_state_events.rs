@@ -0,0 +1,17 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApplicationStateType{
+    Idle,
+    Editor,
+    Game,
+    Loading,
+    Play,
+}
+
+// sent from systems to change the application's state stack. handled by the application
+// between ticks
+#[derive(Clone, Copy, Debug)]
+pub enum ApplicationStateEvent{
+    Push(ApplicationStateType),
+    Pop,
+    Replace(ApplicationStateType),
+}
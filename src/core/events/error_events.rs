@@ -0,0 +1,4 @@
+use crate::core::errors::EngineError;
+
+// sent into the active world whenever something recoverable fails so the ui can show it
+pub struct ErrorEvent(pub EngineError);
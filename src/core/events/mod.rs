@@ -1,4 +1,9 @@
 pub mod project_events;
+pub mod prefab_events;
 pub mod terrain_events;
 pub mod menu_messages;
 pub mod scene_manager_messages;
+pub mod application_state_events;
+pub mod play_mode_events;
+pub mod scene_events;
+pub mod error_events;
@@ -0,0 +1,10 @@
+// sent by the editor's play controls. handled by the application between ticks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayModeEvent{
+    Play,
+    Pause,
+    Resume,
+    // runs a single update tick while paused
+    Step,
+    Stop,
+}
@@ -0,0 +1,16 @@
+use std::string::String;
+use bevy_ecs::entity::Entity;
+use crate::core::plugins::components::TransformComponent;
+
+// saves entity and its children as prefabs/<name> in the current project
+pub struct SavePrefabEvent{
+    pub entity: Entity,
+    pub name: String,
+}
+
+// spawns an instance of a prefab with its root at transform. prefab is the path relative to
+// the project
+pub struct InstantiatePrefabEvent{
+    pub prefab: String,
+    pub transform: TransformComponent,
+}
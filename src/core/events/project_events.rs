@@ -9,4 +9,9 @@ pub struct OpenProjectEvent{
 pub struct CreateProjectEvent{
     pub project_path: String,
     pub scene_name: String,
-}
\ No newline at end of file
+}
+
+// saves the scene under a new name in the current project and makes it the current scene
+pub struct SaveAsEvent{
+    pub scene_name: String,
+}
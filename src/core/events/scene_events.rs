@@ -0,0 +1,23 @@
+use std::string::String;
+
+use crate::core::plugins::components::SceneHandle;
+
+// sent into the active world once an additive scene's entities have been spawned
+pub struct SceneLoadedEvent{
+    pub handle: SceneHandle,
+    pub path: String,
+}
+
+// sent into the active world once an additive scene's entities have been despawned
+pub struct SceneUnloadedEvent{
+    pub handle: SceneHandle,
+}
+
+// sent into the active world while a scene loads in the background. the last one, with a
+// progress of 1.0, goes into the new scene once it's swapped in
+pub struct SceneLoadProgressEvent{
+    pub path: String,
+    // 0.0 to 1.0
+    pub progress: f32,
+    pub stage: &'static str,
+}
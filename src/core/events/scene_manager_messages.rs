@@ -1,11 +1,22 @@
 use std::string::String;
 
+use crate::core::plugins::components::SceneHandle;
+
 #[derive(Clone)]
 pub enum SceneManagerMessage{
+    // opens scene_name from the project at path, or the manifest's default scene if it's None
     OpenProject{
         path: String,
-        scene_name: String,
+        scene_name: Option<String>,
     },
     CloseProject,
+    // spawns the scene at path into the active world next to what's already there
+    LoadSceneAdditive{
+        path: String,
+    },
+    // despawns everything an additive scene spawned
+    UnloadScene{
+        handle: SceneHandle,
+    },
 
 }
\ No newline at end of file
@@ -4,6 +4,7 @@ use winit::event::ModifiersState;
 use winit::event::VirtualKeyCode;
 use super::super::managers::manager::Manager;
 use std::collections::VecDeque;
+use std::collections::HashSet;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -16,6 +17,8 @@ use log;
 #[derive(Clone, Resource, Default)]
 pub struct KeyInputQueue{
     pub queue: VecDeque<VirtualKeyCode>,
+    // keys that are currently down. unlike the queue this isn't cleared every update
+    pub held: HashSet<VirtualKeyCode>,
     pub modifiers_state: ModifiersState,
 }
 
@@ -88,12 +91,20 @@ impl InputManager {
     pub fn handle_key_input(&mut self, key_pressed: Option<VirtualKeyCode>){
         log::debug!("Key input picked up by InputManager...");
         match key_pressed {
-            Some(key) => self.key_input_queue.queue.push_back(key),
+            Some(key) => {
+                self.key_input_queue.queue.push_back(key);
+                self.key_input_queue.held.insert(key);
+            },
             _ => (),
         }
         self.current_key_pressed = key_pressed;
     }
 
+    // handle key release
+    pub fn handle_key_release(&mut self, key_released: VirtualKeyCode){
+        self.key_input_queue.held.remove(&key_released);
+    }
+
     pub fn handle_mouse_button(&mut self, button: &u32, state: &ElementState){
         // button is 1: left, 2: middle, 3: right, so this shifts to zero index
         let button_index: usize = (button - 1) as usize;  
@@ -125,6 +136,10 @@ impl InputManager {
         scene.insert_resource(self.key_input_queue.clone());
     }
 
+    pub fn clear_held_keys(&mut self) {
+        self.key_input_queue.held.clear();
+    }
+
     pub fn clear_input_state(&mut self) {
         self.current_key_pressed = None;
         self.key_input_queue.queue.clear();
@@ -10,4 +10,4 @@ pub use plugin_manager::PluginManager;
 
 pub use scene_manager::SceneManager;
 pub use scene_manager::SceneManagerMessagePump;
-pub use scene_manager::SceneManagerUpdateResults;
\ No newline at end of file
+pub use scene_manager::SceneManagerUpdateResults;
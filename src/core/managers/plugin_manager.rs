@@ -1,4 +1,6 @@
 use crate::core::plugins::Plugin;
+use crate::core::scene::{MigrationRegistry, ScheduleRegistry};
+use bevy_reflect::TypeRegistry;
 
 
 use libloading::Error as LibError;
@@ -22,6 +24,8 @@ use libloading::os::unix::Symbol;
 pub struct PluginManager {
     plugins: Vec<Box<dyn Plugin>>,
     loaded_libraries: Vec<Library>,
+    // the files plugins were loaded from, so a library isn't loaded twice
+    loaded_paths: Vec<String>,
 }
 
 impl PluginManager {
@@ -29,6 +33,7 @@ impl PluginManager {
         PluginManager {
             plugins: Vec::new(),
             loaded_libraries: Vec::new(),
+            loaded_paths: Vec::new(),
         }
     }
 
@@ -53,14 +58,70 @@ impl PluginManager {
         let boxed_raw = constructor();
 
         let plugin = Box::from_raw(boxed_raw);
+        self.add_plugin(plugin);
+        self.loaded_paths.push(filename.as_ref().to_string_lossy().to_string());
+
+
+        Ok(())
+    }
+
+    /// Add a plugin that's compiled into the application rather than loaded
+    /// from a library.
+    pub fn add_plugin(&mut self, plugin: Box<dyn Plugin>) {
         log::debug!("Loaded plugin: {}", plugin.name());
         plugin.on_plugin_load();
         self.plugins.push(plugin);
+    }
+    
+    /// Whether a plugin has already been loaded from `filename`.
+    pub fn is_loaded<P: AsRef<OsStr>>(&self, filename: P) -> bool {
+        let filename = filename.as_ref().to_string_lossy();
+        self.loaded_paths.iter().any(|p| *p == filename)
+    }
 
+    /// Let every loaded plugin add its systems and stages to `schedules`.
+    pub fn register_schedules(&self, schedules: &mut ScheduleRegistry) {
+        for plugin in self.plugins.iter() {
+            log::debug!("Registering schedules for {:?}", plugin.name());
+            plugin.register_schedules(schedules);
+        }
+    }
 
-        Ok(())
+    /// Let the most recently loaded plugin add its systems and stages to `schedules`.
+    pub fn register_last_plugin_schedules(&self, schedules: &mut ScheduleRegistry) {
+        if let Some(plugin) = self.plugins.last() {
+            plugin.register_schedules(schedules);
+        }
     }
-    
+
+    /// Let every loaded plugin add its scene migrations to `migrations`.
+    pub fn register_migrations(&self, migrations: &mut MigrationRegistry) {
+        for plugin in self.plugins.iter() {
+            plugin.register_migrations(migrations);
+        }
+    }
+
+    /// Let the most recently loaded plugin add its scene migrations to `migrations`.
+    pub fn register_last_plugin_migrations(&self, migrations: &mut MigrationRegistry) {
+        if let Some(plugin) = self.plugins.last() {
+            plugin.register_migrations(migrations);
+        }
+    }
+
+    /// Let every loaded plugin add its reflected types to `registry`.
+    pub fn register_types(&self, registry: &mut TypeRegistry) {
+        for plugin in self.plugins.iter() {
+            plugin.register_types(registry);
+        }
+    }
+
+    /// Let the most recently loaded plugin add its reflected types to `registry`.
+    pub fn register_last_plugin_types(&self, registry: &mut TypeRegistry) {
+        if let Some(plugin) = self.plugins.last() {
+            plugin.register_types(registry);
+        }
+    }
+
     /// Unload all plugins and loaded plugin libraries, making sure to fire 
     /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
     pub fn unload(&mut self) {
@@ -74,6 +135,7 @@ impl PluginManager {
         for lib in self.loaded_libraries.drain(..) {
             drop(lib);
         }
+        self.loaded_paths.clear();
     }
 }
 
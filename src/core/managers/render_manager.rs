@@ -44,10 +44,15 @@ use vulkano::{
         SwapchainAcquireFuture,
         SwapchainCreateInfo,
         SwapchainPresentInfo,
+        PresentMode,
     },
     memory::{
         allocator::StandardMemoryAllocator,
     },
+    buffer::{
+        BufferUsage,
+        CpuAccessibleBuffer,
+    },
     swapchain,
     image::{
         view::{
@@ -73,6 +78,7 @@ use vulkano::{
         SubpassContents,
         SecondaryAutoCommandBuffer,
         RenderPassBeginInfo,
+        CopyImageToBufferInfo,
         allocator::StandardCommandBufferAllocator,
         SubpassContents::Inline
     },
@@ -90,9 +96,11 @@ use winit::{
         EventLoop
     },
     window::{
+        Fullscreen,
         Window,
         WindowBuilder
     },
+    dpi::LogicalSize,
 };
 
 // egui
@@ -101,9 +109,14 @@ use egui::{self, FullOutput};
 use egui::Context;
 use winit::event_loop::EventLoopWindowTarget;
 
+use crate::core::project::WindowSettings;
+
 
 // std imports
 use std::sync::{Arc};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 
 // math
 use ember_math::Matrix4f;
@@ -218,6 +231,7 @@ pub struct RenderManager{
     pub images: Vec<Arc<ImageView<SwapchainImage>>>,
     pub scene_state: Arc<SceneState>,
     pub allocators: VulkanAllocators,
+    pub screenshot_request: Option<PathBuf>,
 }
 
 impl RenderManager{
@@ -383,6 +397,7 @@ impl RenderManager{
             images: images,
             scene_state: scene_state,
             allocators: allocator_set,
+            screenshot_request: None,
         };
 
         (render_manager, event_loop)
@@ -499,6 +514,12 @@ impl RenderManager{
         log::debug!("ending render pass");
         command_buffer_builder.end_render_pass().unwrap();
 
+        // copy the finished image out if someone asked for a screenshot
+        let screenshot = match self.screenshot_request.take() {
+            Some(path) => Some((path, self.copy_swapchain_image_to_buffer(&mut command_buffer_builder, image_num))),
+            None => None,
+        };
+
         // build command buffer
         log::debug!("Building command buffer");
         // let command_buffer = command_buffer_builder.build().unwrap();
@@ -525,6 +546,10 @@ impl RenderManager{
         // submit and render
         self.submit_command_buffer_and_render(future_mut, command_buffer, image_num);
 
+        if let Some((path, buffer)) = screenshot {
+            self.write_screenshot(&path, buffer);
+        }
+
         let mut world = scene.get_world().unwrap();
         world
             .get_resource_mut::<EguiState>()
@@ -533,6 +558,79 @@ impl RenderManager{
             .free_textures();
     }
 
+    // the screenshot is captured at the end of the next draw call
+    pub fn request_screenshot<P: AsRef<Path>>(&mut self, path: P){
+        self.screenshot_request = Some(path.as_ref().to_path_buf());
+    }
+
+    fn copy_swapchain_image_to_buffer(
+        &self,
+        command_buffer_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer, Arc<StandardCommandBufferAllocator>>,
+        image_num: u32
+    ) -> Arc<CpuAccessibleBuffer<[u8]>> {
+        log::debug!("Copying swapchain image {} for screenshot", image_num);
+        let [width, height] = self.swapchain.image_extent();
+        let buffer = CpuAccessibleBuffer::from_iter(
+            &self.allocators.memory_allocator(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            (0..width * height * 4).map(|_| 0u8)
+        ).expect("Couldn't create screenshot buffer.");
+
+        let image = self.images[image_num as usize].image().clone();
+        command_buffer_builder
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))
+            .expect("Couldn't copy swapchain image to screenshot buffer.");
+        buffer
+    }
+
+    fn write_screenshot(&self, path: &Path, buffer: Arc<CpuAccessibleBuffer<[u8]>>){
+        log::info!("Writing screenshot to {}", path.display());
+        // make sure the copy has actually happened before reading the buffer
+        if let Err(e) = self.device.wait_idle() {
+            log::error!("Couldn't wait for device before screenshot: {:?}", e);
+            return;
+        }
+
+        // png wants RGBA. anything that isn't four 8 bit channels would come out garbled
+        let format = self.swapchain.image_format();
+        let swap_red_and_blue = match screenshot_channel_swap(format) {
+            Some(swap) => swap,
+            None => {
+                log::error!("Couldn't write screenshot {}: swapchain format {:?} isn't supported", path.display(), format);
+                return;
+            }
+        };
+
+        let [width, height] = self.swapchain.image_extent();
+        let mut pixels = buffer.read().expect("Couldn't read screenshot buffer.").to_vec();
+        if swap_red_and_blue {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        let file = match File::create(path) {
+            Ok(f) => f,
+            Err(e) => {
+                log::error!("Couldn't create screenshot file {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let result = encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels));
+        if let Err(e) = result {
+            log::error!("Couldn't write screenshot {}: {}", path.display(), e);
+        }
+    }
+
     fn submit_command_buffer_and_render(
         &mut self,
         acquire_future: Box<dyn GpuFuture>,
@@ -720,6 +818,55 @@ impl RenderManager{
         (required_extensions, device_extensions)
     }
 
+    // applies a project's window settings. without vsync frames are presented as soon as they're
+    // ready, if the surface supports it
+    pub fn apply_window_settings(&mut self, settings: &WindowSettings){
+        log::info!("Applying window settings {:?}...", settings);
+        let binding = self.surface.clone();
+        let window = binding.object().unwrap().downcast_ref::<Window>().unwrap();
+        window.set_title(&settings.title);
+        if settings.fullscreen {
+            window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        } else {
+            window.set_fullscreen(None);
+            window.set_inner_size(LogicalSize::new(settings.width, settings.height));
+        }
+
+        let present_mode = if settings.vsync {
+            PresentMode::Fifo
+        } else {
+            let supported: Vec<PresentMode> = self.device
+                .physical_device()
+                .surface_present_modes(&self.surface)
+                .map(|modes| modes.collect())
+                .unwrap_or_default();
+            [PresentMode::Mailbox, PresentMode::Immediate]
+                .iter()
+                .copied()
+                .find(|mode| supported.contains(mode))
+                .unwrap_or(PresentMode::Fifo)
+        };
+        if present_mode != self.swapchain.present_mode() {
+            match self.swapchain.recreate(SwapchainCreateInfo{
+                present_mode,
+                image_extent: window.inner_size().into(),
+                ..self.swapchain.create_info()
+            }) {
+                Ok((swapchain, images)) => {
+                    self.scene_state.scale_scene_state_to_images(&images);
+                    self.images = images
+                        .into_iter()
+                        .map(|image| ImageView::new_default(image).unwrap())
+                        .collect();
+                    self.swapchain = swapchain;
+                },
+                Err(e) => log::error!("Couldn't change the present mode to {:?}: {:?}", present_mode, e),
+            }
+        }
+        // the size change comes through as a resize, but the swapchain may be out of date first
+        self.recreate_swapchain = true;
+    }
+
     // creates a surface and ties it to the event loop
     pub fn create_event_loop_and_surface(instance: Arc<Instance>) -> (EventLoop<()>, Arc<vulkano::swapchain::Surface>) {
         let event_loop = EventLoop::new();
@@ -828,6 +975,7 @@ impl RenderManager{
                 image_extent: window.inner_size().into(),
                 image_usage: ImageUsage {
                     color_attachment: true,
+                    transfer_src: true,
                     ..ImageUsage::empty()
                 },
                 composite_alpha: surface_capabilities
@@ -921,4 +1069,18 @@ fn submit_render_system_command_buffers_to_render_pass(scene: &mut Scene<Active>
         log::debug!("Submitting lighting  buffer");
         command_buffer_builder.execute_commands(buff).expect("Failed to execute command");
     }
-}
\ No newline at end of file
+}
+
+// whether the red and blue channels of a swapchain image have to be swapped to get RGBA. None
+// for formats a screenshot can't be written from
+fn screenshot_channel_swap(format: Format) -> Option<bool> {
+    match format {
+        Format::B8G8R8A8_UNORM | Format::B8G8R8A8_SRGB => Some(true),
+        // packed ABGR is laid out as RGBA bytes in memory
+        Format::R8G8B8A8_UNORM
+        | Format::R8G8B8A8_SRGB
+        | Format::A8B8G8R8_UNORM_PACK32
+        | Format::A8B8G8R8_SRGB_PACK32 => Some(false),
+        _ => None,
+    }
+}
@@ -3,10 +3,14 @@ use std::cell::{
     RefCell,
     RefMut
 };
+use std::collections::HashSet;
 use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 use std::path::Path;
 use std::fs;
-use thiserror::Error;
+use crate::core::errors::EngineError;
+use crate::core::project::CurrentProject;
 use crate::core::{
     scene::{
         Scene,
@@ -15,15 +19,26 @@ use crate::core::{
         Staged,
     },
 };
-use serde::de::DeserializeSeed;
 
 use crate::core::events::scene_manager_messages::SceneManagerMessage;
 
 
-use bevy_ecs::entity::EntityMap;
+use bevy_ecs::entity::{Entity, EntityMap};
+use bevy_ecs::event::Events;
+use bevy_hierarchy::{BuildWorldChildren, Children, DespawnRecursiveExt};
+use bevy_reflect::TypeRegistryArc;
 use crate::core::scene::TypeRegistryResource;
-use crate::core::scene::SceneDeserializer;
+use crate::core::scene::scene_format::parse_scene;
+use crate::core::scene::MigrationRegistry;
 use crate::core::scene::DynamicScene;
+use crate::core::plugins::components::{SceneHandle, SceneMemberComponent, PrefabMemberComponent, CameraComponent};
+use crate::core::scene::{DynamicSceneBuilder, SceneDiff, SceneWatcher};
+use crate::core::scene::scene_watcher::SCENE_WATCH_INTERVAL;
+use crate::core::systems::project_systems::saveable_entities;
+use crate::core::application::PlayMode;
+use crate::core::scene::prefab::refresh_all_prefab_instances;
+use crate::core::events::scene_events::{SceneLoadedEvent, SceneUnloadedEvent, SceneLoadProgressEvent};
+use crate::core::events::error_events::ErrorEvent;
 use bevy_ecs::prelude::Resource;
 
 
@@ -52,27 +67,56 @@ impl Default for SceneManagerMessagePump{
 #[derive(Debug)]
 pub enum SceneManagerUpdateResults{
     NoUpdate,
-    NewSceneOpened    
+    // a scene started loading on a background thread
+    SceneLoadStarted,
+    // the background load is done. build_loaded_scene and replace_active_scene swap it in
+    SceneLoadFinished,
+    // the project being opened needs these plugin libraries. its scene starts loading on the
+    // next update
+    ProjectPluginsNeeded(Vec<String>),
+}
+
+// sent from the loading thread back to the scene manager
+enum SceneLoadMessage{
+    Progress(f32, &'static str),
+    Finished(Result<DynamicScene, EngineError>),
+}
+
+// a scene being read and deserialized on a background thread
+struct SceneLoadTask{
+    path: String,
+    // the project the scene belongs to
+    project: CurrentProject,
+    type_registry: TypeRegistryArc,
+    migrations: MigrationRegistry,
+    receiver: Receiver<SceneLoadMessage>,
+}
+
+// a finished background load waiting to be built
+struct LoadedScene{
+    scene: DynamicScene,
+    path: String,
+    project: CurrentProject,
+    // what the scene was read with. the scene built from it gets the same
+    type_registry: TypeRegistryArc,
+    migrations: MigrationRegistry,
 }
 
-#[derive(Debug, Error)]
-pub enum SceneManagerUpdateError{
-    #[error("No active scene")]
-    NoActiveScene,
-    #[error("No world on current scene")]
-    NoWorldOnScene,
-    #[error("Unknown scene manager message type")]
-    UnknownMessageType,
-    #[error("Error reading ron scene")]
-    RonReadError,
-    #[error("Error in constructing scene from ron file")]
-    DeserializationError
-}   
+
 
 pub struct SceneManager{
     active_scene: Option<RefCell<Scene<Active>>>,
     staged_scene: Option<RefCell<Scene<Staged>>>,
-    scene_counter: i16,
+    // used to hand out scene handles
+    scene_counter: u32,
+    // scenes loaded additively into the active scene, and the files they came from
+    loaded_scenes: Vec<(SceneHandle, String)>,
+    scene_load: Option<SceneLoadTask>,
+    loaded_scene: Option<LoadedScene>,
+    // the open project's scenes directory, for reloading scenes changed on disk
+    scene_watcher: Option<SceneWatcher>,
+    // a project waiting for the application to load its plugins
+    opening_project: Option<CurrentProject>,
 }
 
 
@@ -88,6 +132,11 @@ impl SceneManager{
             active_scene: None,
             staged_scene: None,
             scene_counter: 0,
+            loaded_scenes: Vec::new(),
+            scene_load: None,
+            loaded_scene: None,
+            scene_watcher: None,
+            opening_project: None,
         }
     }
 
@@ -98,87 +147,515 @@ impl SceneManager{
         log::info!("Shutting down scene manager...");
     }
 
-    pub fn update(&mut self) -> Result<SceneManagerUpdateResults, SceneManagerUpdateError>{
+    pub fn update(&mut self) -> Result<SceneManagerUpdateResults, EngineError>{
+        // check on the scene being loaded in the background first
+        if self.poll_scene_load()? {
+            return Ok(SceneManagerUpdateResults::SceneLoadFinished);
+        }
 
         // let mut scene = self.get_active_scene().unwrap();
-        let (messages, type_registry) = {
+        let (messages, type_registry, migrations) = {
             let mut scene = match self.get_active_scene() {
                 Some(s) => s, 
-                None => return Err(SceneManagerUpdateError::NoActiveScene)
+                None => return Err(EngineError::NoActiveScene)
             };
             let world = match scene.get_world() {
                 Some(w) => w,
-                None => return Err(SceneManagerUpdateError::NoWorldOnScene)
+                None => return Err(EngineError::NoWorldOnScene)
             };
             
-            let pump = world.get_resource::<SceneManagerMessagePump>().expect("Event channel not found");
-            let type_registry = &world.get_resource::<TypeRegistryResource>().expect("Type registry not found.").0.clone();
+            let pump = match world.get_resource::<SceneManagerMessagePump>() {
+                Some(p) => p,
+                None => return Err(EngineError::MissingResource("SceneManagerMessagePump"))
+            };
+            let type_registry = match world.get_resource::<TypeRegistryResource>() {
+                Some(r) => r.0.clone(),
+                None => return Err(EngineError::MissingResource("TypeRegistryResource"))
+            };
+
+            // messages wait until a project waiting on its plugins has started loading
+            let messages = if self.opening_project.is_some() {
+                Vec::new()
+            } else {
+                let messages = (*pump.messages.lock().unwrap()).clone();
+                pump.clear();
+                messages
+            };
 
-            let messages = (*pump.messages.lock().unwrap()).clone();
-            pump.clear();
+            // scenes can still be loaded without migrations, they just can't be upgraded
+            let migrations = world.get_resource::<MigrationRegistry>().cloned().unwrap_or_default();
 
-            (messages, type_registry.clone())
+            (messages, type_registry, migrations)
         };
+        if let Some(project) = self.opening_project.take() {
+            self.start_project_load(project, type_registry, migrations);
+            return Ok(SceneManagerUpdateResults::SceneLoadStarted);
+        }
         for m in messages.iter(){
             match m {
                 SceneManagerMessage::OpenProject {path, scene_name} => {
-                    // i think here is where i deserde and tell the application that it needs to re-prep
-                    let mut scene_path = path.clone();
-                    scene_path.push_str("/scenes/");
-                    scene_path.push_str(&scene_name.clone().to_owned());
-                    log::info!(
-                        "{}",
-                        format!(
-                            "Opening Project {} with scene {} - exists: {}",
-                            path,
-                            scene_path,
-                            self.does_save_exist(scene_path.clone()),
-                        )
-                    );
-                    let ron_str = match fs::read_to_string(scene_path) {
-                        Ok(s) => s,
-                        _ => return Err(SceneManagerUpdateError::RonReadError)
-                    };
-                    log::info!("Got ron string.");
-                    let scene_bytes = ron_str.as_bytes();
-                    let mut deserializer = ron::de::Deserializer::from_bytes(scene_bytes).expect("Error making deserializer.");
-                    let scene_deserializer = SceneDeserializer {
-                        type_registry: &*type_registry.read(),
-                    };
-                    log::info!("Created deserializer.");
-
-                    // Get the metadata for the file
-                    let metadata = match fs::metadata(path) {
-                        Ok(metadata) => metadata,
-                        Err(_e) => return Err(SceneManagerUpdateError::DeserializationError)
-                    };
-                    
-                    let loaded_scene = DynamicScene::default();
-
-                    if metadata.len() != 0 {
-                        let _loaded_scene = match scene_deserializer.deserialize(&mut deserializer){
-                            Ok(s) => s,
-                            _ => return Err(SceneManagerUpdateError::DeserializationError)
-                        };
+                    let project = CurrentProject::open(path, scene_name.as_deref())?;
+                    // plugin types have to be registered before the scene is read
+                    let plugins = project.plugin_paths();
+                    if !plugins.is_empty() {
+                        self.opening_project = Some(project);
+                        return Ok(SceneManagerUpdateResults::ProjectPluginsNeeded(plugins));
+                    }
+                    self.start_project_load(project, type_registry.clone(), migrations.clone());
+                    return Ok(SceneManagerUpdateResults::SceneLoadStarted);
+                },
+                SceneManagerMessage::LoadSceneAdditive {path} => {
+                    self.load_scene_additive(path)?;
+                },
+                SceneManagerMessage::UnloadScene {handle} => {
+                    self.unload_scene(*handle)?;
+                },
+                _ => return Err(EngineError::UnknownMessageType)
+            }
+        }
+        self.reload_changed_scenes()?;
+        Ok(SceneManagerUpdateResults::NoUpdate)
+    }
+
+    // reloads the open scene and any additive scenes whose files changed on disk. only while
+    // editing, changes made while playing are picked up once play stops
+    fn reload_changed_scenes(&mut self) -> Result<(), EngineError> {
+        if self.is_loading() {
+            return Ok(());
+        }
+        let (scenes_dir, current_scene_path) = {
+            let mut scene = match self.get_active_scene() {
+                Some(s) => s,
+                None => return Ok(()),
+            };
+            let world = match scene.get_world() {
+                Some(w) => w,
+                None => return Ok(()),
+            };
+            if world.get_resource::<PlayMode>().copied() != Some(PlayMode::Editing) {
+                return Ok(());
+            }
+            match world.get_resource::<CurrentProject>() {
+                Some(project) => (project.scenes_dir(), project.current_scene_path()),
+                None => return Ok(()),
+            }
+        };
+
+        // start over when a different project is opened
+        if self.scene_watcher.as_ref().map(|w| w.dir() != scenes_dir).unwrap_or(true) {
+            self.scene_watcher = Some(SceneWatcher::new(scenes_dir, SCENE_WATCH_INTERVAL));
+            return Ok(());
+        }
+        let changed = self.scene_watcher.as_mut().unwrap().poll();
+
+        // a file that can't be read is reported and left for the next change. the others are
+        // still reloaded
+        for path in changed.iter() {
+            let additive: Vec<(SceneHandle, String)> = self.loaded_scenes
+                .iter()
+                .filter(|(_, p)| same_file(Path::new(p), path))
+                .cloned()
+                .collect();
+            for (handle, scene_path) in additive {
+                log::info!("{} changed on disk, reloading it...", scene_path);
+                if let Err(e) = self.reload_additive_scene(handle, &scene_path) {
+                    self.report_error(e);
+                }
+            }
+            if same_file(path, &current_scene_path) {
+                if let Err(e) = self.reload_active_scene(&path.to_string_lossy()) {
+                    self.report_error(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // the scene that's loaded is only unloaded once the file has been read
+    fn reload_additive_scene(&mut self, handle: SceneHandle, scene_path: &str) -> Result<SceneHandle, EngineError> {
+        let file_scene = self.read_for_active_scene(scene_path)?;
+        self.unload_scene(handle)?;
+        self.spawn_scene_additive(scene_path, &file_scene)
+    }
+
+    // logs the error and sends it to the active scene so the ui can show it
+    fn report_error(&mut self, error: EngineError){
+        log::error!("{}", error);
+        if let Some(mut scene) = self.get_active_scene() {
+            if let Some(mut world) = scene.get_world() {
+                world.send_event(ErrorEvent(error));
+            }
+        }
+    }
+
+    // replaces the active world's scene entities and resources with what's in the scene file.
+    // the editor interface, additive scenes and the current camera are kept. the file's cameras
+    // are only spawned when the world has none, so a reload doesn't move the view. returns false
+    // if the file matches the world, like after the editor saved it
+    pub fn reload_active_scene(&mut self, scene_path: &str) -> Result<bool, EngineError> {
+        let mut active_scene = match self.get_active_scene() {
+            Some(s) => s,
+            None => return Err(EngineError::NoActiveScene)
+        };
+        {
+            let mut world = match active_scene.get_world() {
+                Some(w) => w,
+                None => return Err(EngineError::NoWorldOnScene)
+            };
+            let type_registry = match world.get_resource::<TypeRegistryResource>() {
+                Some(r) => r.0.clone(),
+                None => return Err(EngineError::MissingResource("TypeRegistryResource"))
+            };
+            let migrations = world.get_resource::<MigrationRegistry>().cloned().unwrap_or_default();
+            let file_scene = Self::read_scene_file(scene_path, &type_registry, &migrations)?;
+
+            let scene_entities = saveable_entities(&world);
+            let world_scene = {
+                let mut builder = DynamicSceneBuilder::from_world_with_type_registry(&world, type_registry.clone());
+                builder.extract_entities(scene_entities.iter().copied());
+                builder.extract_resources();
+                builder.build()
+            };
+            if SceneDiff::between(&world_scene, &file_scene, &type_registry)?.is_empty() {
+                return Ok(false);
+            }
+            log::info!("{} changed on disk, reloading it...", scene_path);
+
+            let cameras: Vec<Entity> = scene_entities
+                .iter()
+                .copied()
+                .filter(|e| world.get::<CameraComponent>(*e).is_some())
+                .collect();
+            let replaced: HashSet<Entity> = scene_entities
+                .into_iter()
+                .filter(|e| !cameras.contains(e))
+                .collect();
+            // kept entities under replaced ones are taken out of the hierarchy first, so they
+            // aren't despawned with them or left with a parent that's gone
+            for entity in replaced.iter() {
+                let kept: Vec<Entity> = world
+                    .get::<Children>(*entity)
+                    .map(|children| children.iter().copied().filter(|c| !replaced.contains(c)).collect())
+                    .unwrap_or_default();
+                if !kept.is_empty() {
+                    world.entity_mut(*entity).remove_children(&kept);
+                }
+            }
+            for entity in replaced {
+                // already gone if it was under another replaced entity
+                if world.get_entity(entity).is_some() {
+                    world.entity_mut(entity).despawn_recursive();
+                }
+            }
+
+            let mut entity_map = EntityMap::default();
+            if let Err(e) = file_scene.write_to_world_with(&mut world, &mut entity_map, &type_registry) {
+                return Err(EngineError::SceneSpawn{
+                    path: String::from(scene_path),
+                    reason: e.to_string(),
+                });
+            }
+            // the camera being looked through wins over the one in the file
+            if !cameras.is_empty() {
+                let spawned_cameras: Vec<Entity> = entity_map
+                    .values()
+                    .filter(|e| world.get::<CameraComponent>(*e).is_some())
+                    .collect();
+                for entity in spawned_cameras {
+                    if world.get_entity(entity).is_some() {
+                        world.entity_mut(entity).despawn_recursive();
                     }
+                }
+            }
+            refresh_all_prefab_instances(&mut world);
+        }
+        // new entities need their geometry and renderables set up
+        active_scene.run_setup_schedule();
+        Ok(true)
+    }
 
-                    log::info!("Down Syncing active scene...");
-                    self.stage_active_scene();
-                    self.deactivate_staged_scene();
+    fn start_project_load(&mut self, project: CurrentProject, type_registry: TypeRegistryArc, migrations: MigrationRegistry){
+        let scene_path = project.current_scene_path().to_string_lossy().to_string();
+        log::info!(
+            "Opening project {} with scene {} - exists: {}",
+            project.manifest.name,
+            scene_path,
+            self.does_save_exist(scene_path.clone()),
+        );
+        self.start_scene_load(scene_path, project, type_registry, migrations);
+    }
 
-                    log::info!("Creating and staging new scene from ron...");
-                    let mut staged_scene = Scene::<Staged>::new(); 
-                    let world = staged_scene.world.take().expect("No world on new scene");
-                    loaded_scene.write_to_world(&mut world.borrow_mut(), &mut EntityMap::default());
-                    staged_scene.world.replace(world);
-                    self.set_staged_scene(staged_scene);
+    // reads and deserializes the scene on a background thread. update polls it
+    fn start_scene_load(&mut self, scene_path: String, project: CurrentProject, type_registry: TypeRegistryArc, migrations: MigrationRegistry){
+        if let Some(task) = self.scene_load.take() {
+            log::warn!("Dropping the load of {} to load {}", task.path, scene_path);
+        }
+        let (sender, receiver) = mpsc::channel();
+        let thread_path = scene_path.clone();
+        let thread_registry = type_registry.clone();
+        let thread_migrations = migrations.clone();
+        thread::spawn(move || {
+            // the receiver is gone if the load was dropped, so send errors are ignored
+            let _ = sender.send(SceneLoadMessage::Progress(0.0, "Reading scene file"));
+            let bytes = match fs::read(&thread_path) {
+                Ok(b) => b,
+                Err(e) => {
+                    let _ = sender.send(SceneLoadMessage::Finished(Err(EngineError::SceneRead{
+                        path: thread_path.clone(),
+                        reason: e.to_string(),
+                    })));
+                    return;
+                }
+            };
+            let _ = sender.send(SceneLoadMessage::Progress(0.5, "Deserializing scene"));
+            let result = parse_scene(&thread_path, &bytes, &thread_registry, &thread_migrations);
+            let _ = sender.send(SceneLoadMessage::Finished(result));
+        });
+        self.scene_load = Some(SceneLoadTask{
+            path: scene_path,
+            project,
+            type_registry,
+            migrations,
+            receiver,
+        });
+    }
 
-                    return Ok(SceneManagerUpdateResults::NewSceneOpened);
+    // forwards progress from the loading thread to the active world. returns true once the
+    // scene is loaded and waiting for build_loaded_scene
+    fn poll_scene_load(&mut self) -> Result<bool, EngineError> {
+        let task = match self.scene_load.as_ref() {
+            Some(t) => t,
+            None => return Ok(false),
+        };
+        let path = task.path.clone();
+        let project = task.project.clone();
+        let type_registry = task.type_registry.clone();
+        let migrations = task.migrations.clone();
+        let mut progress = Vec::new();
+        let mut finished = None;
+        loop {
+            match task.receiver.try_recv() {
+                Ok(SceneLoadMessage::Progress(p, stage)) => progress.push((p, stage)),
+                Ok(SceneLoadMessage::Finished(result)) => {
+                    finished = Some(result);
+                    break;
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished = Some(Err(EngineError::SceneDeserialization{
+                        path: task.path.clone(),
+                        reason: String::from("the loading thread stopped without a result"),
+                    }));
+                    break;
                 },
-                _ => return Err(SceneManagerUpdateError::UnknownMessageType)
             }
         }
-        Ok(SceneManagerUpdateResults::NoUpdate)
+
+        if let Some(mut scene) = self.get_active_scene() {
+            if let Some(mut world) = scene.get_world() {
+                if let Some(mut events) = world.get_resource_mut::<Events<SceneLoadProgressEvent>>() {
+                    for (p, stage) in progress.into_iter() {
+                        events.send(SceneLoadProgressEvent{ path: path.clone(), progress: p, stage });
+                    }
+                }
+            }
+        }
+
+        match finished {
+            Some(Ok(scene)) => {
+                log::info!("Finished loading {}", path);
+                self.scene_load = None;
+                self.loaded_scene = Some(LoadedScene{
+                    scene,
+                    path,
+                    project,
+                    type_registry,
+                    migrations,
+                });
+                Ok(true)
+            },
+            Some(Err(e)) => {
+                log::error!("Couldn't load {}: {}", path, e);
+                self.scene_load = None;
+                Err(e)
+            },
+            None => Ok(false),
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.scene_load.is_some()
+    }
+
+    // builds a staged scene from the one that just finished loading. nothing changes if it
+    // can't be spawned
+    pub fn build_loaded_scene(&mut self) -> Result<Scene<Staged>, EngineError> {
+        let LoadedScene{ scene: loaded_scene, path, project, type_registry, migrations } = match self.loaded_scene.take() {
+            Some(l) => l,
+            None => return Err(EngineError::NoLoadedScene)
+        };
+
+        log::info!("Creating new scene from {}...", path);
+        let mut staged_scene = Scene::<Staged>::new();
+        {
+            let mut world = match staged_scene.get_world() {
+                Some(w) => w,
+                None => return Err(EngineError::NoWorldOnScene)
+            };
+            // plugin types aren't in a new scene's registry until it's prepped
+            world.insert_resource(TypeRegistryResource(type_registry));
+            if let Err(e) = loaded_scene.write_to_world(&mut world, &mut EntityMap::default()) {
+                return Err(EngineError::SceneSpawn{ path, reason: e.to_string() });
+            }
+            world.insert_resource(project);
+            world.insert_resource(migrations);
+            // prefabs may have changed since the scene was saved
+            refresh_all_prefab_instances(&mut world);
+            world.send_event(SceneLoadProgressEvent{ path, progress: 1.0, stage: "Done" });
+        }
+        Ok(staged_scene)
+    }
+
+    // takes the active scene down and stages scene in its place
+    pub fn replace_active_scene(&mut self, scene: Scene<Staged>){
+        log::info!("Down Syncing active scene...");
+        self.stage_active_scene();
+        self.deactivate_staged_scene();
+
+        self.set_staged_scene(scene);
+        // the old world took the additive scenes with it
+        self.loaded_scenes.clear();
+    }
+
+    // spawns a scene file into the active world next to whatever is already there. every entity
+    // it spawns is tagged with the returned handle so it can be unloaded later
+    pub fn load_scene_additive(&mut self, scene_path: &str) -> Result<SceneHandle, EngineError> {
+        log::info!("Loading scene {} additively...", scene_path);
+        let loaded_scene = self.read_for_active_scene(scene_path)?;
+        self.spawn_scene_additive(scene_path, &loaded_scene)
+    }
+
+    fn spawn_scene_additive(&mut self, scene_path: &str, loaded_scene: &DynamicScene) -> Result<SceneHandle, EngineError> {
+        let handle = SceneHandle(self.scene_counter + 1);
+
+        {
+            let mut active_scene = match self.get_active_scene() {
+                Some(s) => s,
+                None => return Err(EngineError::NoActiveScene)
+            };
+            {
+                let mut world = match active_scene.get_world() {
+                    Some(w) => w,
+                    None => return Err(EngineError::NoWorldOnScene)
+                };
+                let type_registry = match world.get_resource::<TypeRegistryResource>() {
+                    Some(r) => r.0.clone(),
+                    None => return Err(EngineError::MissingResource("TypeRegistryResource"))
+                };
+
+                let mut entity_map = EntityMap::default();
+                if let Err(e) = loaded_scene.write_to_world_with(&mut world, &mut entity_map, &type_registry) {
+                    return Err(EngineError::SceneSpawn{
+                        path: String::from(scene_path),
+                        reason: e.to_string(),
+                    });
+                }
+                refresh_all_prefab_instances(&mut world);
+                // prefab instances can gain entities when they're brought up to date
+                let spawned: Vec<Entity> = entity_map.values().collect();
+                let prefab_members: Vec<Entity> = world
+                    .query::<(Entity, &PrefabMemberComponent)>()
+                    .iter(&world)
+                    .filter(|(_, member)| spawned.contains(&member.instance))
+                    .map(|(entity, _)| entity)
+                    .collect();
+                for entity in spawned.into_iter().chain(prefab_members) {
+                    world.entity_mut(entity).insert(SceneMemberComponent{ scene: handle });
+                }
+                if let Some(mut events) = world.get_resource_mut::<Events<SceneLoadedEvent>>() {
+                    events.send(SceneLoadedEvent{ handle, path: String::from(scene_path) });
+                }
+            }
+            // new entities need their geometry and renderables set up
+            active_scene.run_setup_schedule();
+        }
+
+        // only a scene that made it into the world uses up a handle
+        self.scene_counter = handle.0;
+        self.loaded_scenes.push((handle, String::from(scene_path)));
+        log::info!("Loaded scene {} as {:?}", scene_path, handle);
+        Ok(handle)
+    }
+
+    // despawns every entity an additive scene spawned, along with their children
+    pub fn unload_scene(&mut self, handle: SceneHandle) -> Result<(), EngineError> {
+        let index = match self.loaded_scenes.iter().position(|(h, _)| *h == handle) {
+            Some(i) => i,
+            None => return Err(EngineError::NoSuchScene(handle.0))
+        };
+        log::info!("Unloading scene {:?}...", handle);
+
+        {
+            let mut active_scene = match self.get_active_scene() {
+                Some(s) => s,
+                None => return Err(EngineError::NoActiveScene)
+            };
+            let mut world = match active_scene.get_world() {
+                Some(w) => w,
+                None => return Err(EngineError::NoWorldOnScene)
+            };
+            let members: Vec<Entity> = world
+                .query::<(Entity, &SceneMemberComponent)>()
+                .iter(&world)
+                .filter(|(_, member)| member.scene == handle)
+                .map(|(entity, _)| entity)
+                .collect();
+            for entity in members {
+                // might have gone already as the child of another member
+                if world.get_entity(entity).is_some() {
+                    world.entity_mut(entity).despawn_recursive();
+                }
+            }
+            if let Some(mut events) = world.get_resource_mut::<Events<SceneUnloadedEvent>>() {
+                events.send(SceneUnloadedEvent{ handle });
+            }
+        }
+
+        self.loaded_scenes.remove(index);
+        Ok(())
+    }
+
+    pub fn loaded_scenes(&self) -> &Vec<(SceneHandle, String)> {
+        &self.loaded_scenes
+    }
+
+    // reads a scene file with the active world's types and migrations
+    fn read_for_active_scene(&self, scene_path: &str) -> Result<DynamicScene, EngineError> {
+        let mut active_scene = match self.get_active_scene() {
+            Some(s) => s,
+            None => return Err(EngineError::NoActiveScene)
+        };
+        let world = match active_scene.get_world() {
+            Some(w) => w,
+            None => return Err(EngineError::NoWorldOnScene)
+        };
+        let type_registry = match world.get_resource::<TypeRegistryResource>() {
+            Some(r) => r.0.clone(),
+            None => return Err(EngineError::MissingResource("TypeRegistryResource"))
+        };
+        let migrations = world.get_resource::<MigrationRegistry>().cloned().unwrap_or_default();
+        Self::read_scene_file(scene_path, &type_registry, &migrations)
+    }
+
+    fn read_scene_file(scene_path: &str, type_registry: &TypeRegistryArc, migrations: &MigrationRegistry) -> Result<DynamicScene, EngineError> {
+        let bytes = match fs::read(scene_path) {
+            Ok(b) => b,
+            Err(e) => return Err(EngineError::SceneRead{
+                path: String::from(scene_path),
+                reason: e.to_string(),
+            })
+        };
+        parse_scene(scene_path, &bytes, type_registry, migrations)
     }
 
     pub fn prep_staged_scene(&mut self, _scene: &mut Scene<Staged>){
@@ -249,3 +726,11 @@ impl SceneManager{
     }
 
 }
+
+// paths to the same file can be spelled differently, ie with or without a leading ./
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
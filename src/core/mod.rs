@@ -4,4 +4,6 @@ pub mod rendering;
 pub mod plugins;
 pub mod scene;
 pub mod systems;
-pub mod events;
\ No newline at end of file
+pub mod events;
+pub mod errors;
+pub mod project;
@@ -48,6 +48,8 @@ pub struct CameraComponent{
     pub radius: f32,
 }
 
+crate::register_type!(CameraComponent);
+
 impl CameraComponent {
 
     pub fn update_cartesian(&mut self){
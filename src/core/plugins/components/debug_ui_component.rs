@@ -14,6 +14,8 @@ pub struct DebugUiComponent{
     pub terrain_wireframe: bool
 }
 
+crate::register_type!(DebugUiComponent);
+
 impl Default for DebugUiComponent{
     fn default() -> Self {
         DebugUiComponent{
@@ -15,17 +15,28 @@ use bevy_reflect::{
 use bevy_reflect::ReflectSerialize;
 use bevy_reflect::ReflectDeserialize;
 use serde::{Deserialize, Serialize};
-use crate::core::rendering::geometries::Vertex;
+use crate::core::rendering::geometries::{MeshData, Vertex};
 
+// where a custom mesh comes from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MeshSource{
+    // a .ron or .obj mesh file, relative to the project
+    File(String),
+    // kept in the scene file. for procedural meshes
+    Inline(MeshData),
+}
 
-#[derive(Reflect, FromReflect, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Reflect, FromReflect, Clone, PartialEq, Serialize, Deserialize)]
 #[reflect_value(PartialEq, Serialize, Deserialize)]
 pub enum GeometryType{
     Triangle,
     Box,
     Plane,
+    Mesh(MeshSource),
 }
 
+crate::register_type!(GeometryType);
+
 impl Default for GeometryType{
     fn default() -> Self {
         GeometryType::Box
@@ -49,6 +60,8 @@ pub struct GeometryComponent{
     pub geometry_type: GeometryType,
 }
 
+crate::register_type!(GeometryComponent);
+
 impl Default for GeometryComponent{
     fn default() -> Self {
         GeometryComponent{
@@ -73,6 +86,26 @@ impl GeometryComponent{
             geometry_type: t,
         }
     }
+    // a procedural mesh. it's saved with the scene
+    pub fn from_mesh(vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+        let mut geometry = GeometryComponent::default();
+        geometry.set_mesh(vertices, indices);
+        geometry
+    }
+
+    // replaces the mesh with a procedural one so the new vertices are saved too
+    pub fn set_mesh(&mut self, vertices: Vec<Vertex>, indices: Vec<u32>){
+        self.geometry_type = GeometryType::Mesh(MeshSource::Inline(MeshData::new(&vertices, &indices)));
+        self.vertices = vertices;
+        self.indices = indices;
+        self.initialized = false;
+    }
+
+    // a mesh file, relative to the project
+    pub fn from_mesh_file(path: &str) -> Self {
+        GeometryComponent::create(GeometryType::Mesh(MeshSource::File(String::from(path))))
+    }
+
     pub fn default_vertex_buffer() -> Option<Arc<CpuAccessibleBuffer<[Vertex]>>> {
         None
     }
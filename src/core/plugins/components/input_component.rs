@@ -20,6 +20,8 @@ pub struct InputComponent{
     pub key_buffer: VecDeque<VirtualKeyCode>,
 }
 
+crate::register_type!(InputComponent);
+
 impl Default for InputComponent {
     fn default() -> Self {
         InputComponent{
@@ -16,6 +16,8 @@ pub struct DirectionalLightComponent{
     pub color: Vector4f,
 }
 
+crate::register_type!(DirectionalLightComponent);
+
 impl DirectionalLightComponent{
     pub fn new(direction: Vector3f, color: Vector4f) -> Self {
         DirectionalLightComponent{
@@ -40,6 +42,8 @@ pub struct AmbientLightingComponent{
     pub color: Vector3f,
 }
 
+crate::register_type!(AmbientLightingComponent);
+
 impl AmbientLightingComponent{
     pub fn new(color: Vector3f) -> Self{
         AmbientLightingComponent{
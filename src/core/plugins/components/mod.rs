@@ -9,6 +9,8 @@ pub mod light_components;
 pub mod terrain_component;
 pub mod serializer_component;
 pub mod geometry_component;
+pub mod scene_member_component;
+pub mod prefab_component;
 pub mod ui;
 
 pub use input_component::InputComponent;
@@ -16,6 +18,7 @@ pub use camera_component::{CameraComponent, CameraMatrices};
 pub use transform_component::TransformComponent;
 pub use transform_component::TransformUiComponent;
 pub use transform_component::TransformBuilder;
+pub use velocity_component::VelocityComponent;
 pub use debug_ui_component::DebugUiComponent;
 pub use egui_component::EguiComponent;
 pub use renderable_component::RenderableComponent;
@@ -23,10 +26,21 @@ pub use light_components::DirectionalLightComponent;
 pub use light_components::AmbientLightingComponent;
 pub use terrain_component::TerrainComponent;
 pub use terrain_component::TerrainUiComponent;
+pub use terrain_component::TerrainNoise;
+pub use terrain_component::TerrainHeightMap;
 pub use serializer_component::SerializerFlag;
 pub use geometry_component::GeometryComponent;
 pub use geometry_component::GeometryType;
+pub use geometry_component::MeshSource;
+pub use scene_member_component::SceneHandle;
+pub use scene_member_component::SceneMemberComponent;
+pub use prefab_component::PrefabOverride;
+pub use prefab_component::PrefabInstanceComponent;
+pub use prefab_component::PrefabMemberComponent;
 pub use ui::AppInterfaceFlag;
 pub use ui::FileSubMenuComponent;
 pub use ui::FileMenuSaveComponent;
-pub use ui::SceneGraphComponent;
\ No newline at end of file
+pub use ui::SceneGraphComponent;
+pub use ui::LoadingScreenComponent;
+pub use ui::ProjectPickerComponent;
+pub use ui::PlayControlsComponent;
@@ -0,0 +1,67 @@
+use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
+use serde::{
+    Serialize,
+    Deserialize,
+};
+use bevy_reflect::{
+    Reflect,
+    FromReflect
+};
+use bevy_ecs::prelude::ReflectComponent;
+
+// one field of one component that an instance keeps no matter what the prefab says
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Reflect, FromReflect)]
+pub struct PrefabOverride{
+    // id of the entity in the prefab file
+    pub entity: u32,
+    pub component: String,
+    // reflect path to the field, ie "global_position" or "global_position.x"
+    pub field: String,
+    // the field's value as ron
+    pub value: String,
+}
+
+crate::register_type!(PrefabOverride, Vec<PrefabOverride>);
+
+// on the root entity of a prefab instance
+#[derive(Component, Debug, Default, Clone, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(Component)]
+pub struct PrefabInstanceComponent{
+    // prefab file, relative to the project
+    pub prefab: String,
+    pub overrides: Vec<PrefabOverride>,
+}
+
+crate::register_type!(PrefabInstanceComponent);
+
+impl PrefabInstanceComponent{
+    // replaces any override already set for the same field
+    pub fn set_override(&mut self, new_override: PrefabOverride){
+        self.overrides.retain(|o| {
+            o.entity != new_override.entity || o.component != new_override.component || o.field != new_override.field
+        });
+        self.overrides.push(new_override);
+    }
+}
+
+// on every entity spawned from a prefab, the root included
+#[derive(Component, Debug, Clone, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(Component)]
+pub struct PrefabMemberComponent{
+    // root of the instance this entity belongs to
+    pub instance: Entity,
+    // id of the entity in the prefab file
+    pub source: u32,
+}
+
+crate::register_type!(PrefabMemberComponent);
+
+impl Default for PrefabMemberComponent{
+    fn default() -> Self {
+        PrefabMemberComponent{
+            instance: Entity::from_raw(u32::MAX),
+            source: 0,
+        }
+    }
+}
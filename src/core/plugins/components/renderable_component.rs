@@ -21,6 +21,8 @@ pub struct RenderableComponent{
     pub initialized: bool,
 }
 
+crate::register_type!(RenderableComponent);
+
 impl RenderableComponent{
 
     pub fn create() -> Self {
@@ -0,0 +1,25 @@
+use bevy_ecs::component::Component;
+use serde::{
+    Serialize,
+    Deserialize,
+};
+use bevy_reflect::{
+    Reflect,
+    FromReflect
+};
+use bevy_ecs::prelude::ReflectComponent;
+
+// identifies a scene that was loaded additively into the active world
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect, FromReflect)]
+pub struct SceneHandle(pub u32);
+
+crate::register_type!(SceneHandle);
+
+// put on every entity an additive scene spawns so the scene can be unloaded again
+#[derive(Component, Debug, Default, Clone, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(Component)]
+pub struct SceneMemberComponent{
+    pub scene: SceneHandle,
+}
+
+crate::register_type!(SceneMemberComponent);
@@ -17,21 +17,106 @@ use bevy_ecs::prelude::ReflectComponent;
 #[reflect(Component)]
 pub struct TerrainUiComponent;
 
+crate::register_type!(TerrainUiComponent);
+
+// fractal noise the terrain's heights are generated from. the defaults are a single octave
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect, FromReflect)]
+pub struct TerrainNoise{
+    pub frequency: f64,
+    pub octaves: u32,
+    // frequency multiplier from one octave to the next
+    pub lacunarity: f64,
+    // amplitude multiplier from one octave to the next
+    pub persistence: f64,
+}
+
+crate::register_type!(TerrainNoise);
+
+impl Default for TerrainNoise {
+    fn default() -> Self {
+        TerrainNoise{
+            frequency: 1.0,
+            octaves: 1,
+            lacunarity: 2.0,
+            persistence: 0.5,
+        }
+    }
+}
+
+// where a terrain's heights come from when it's loaded. hand edited terrain has to be baked,
+// generating it again would lose the edits
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect, FromReflect)]
+pub enum TerrainHeightMap{
+    // from the noise settings
+    Generated,
+    // kept in the scene file, size * size heights row by row
+    Embedded(Vec<f32>),
+    // a height map file written with write_height_map, relative to the project
+    File(String),
+}
+
+crate::register_type!(TerrainHeightMap, Vec<f32>);
+
+impl Default for TerrainHeightMap {
+    fn default() -> Self {
+        TerrainHeightMap::Generated
+    }
+}
+
+// the mesh isn't saved. it's generated again from the parameters when the scene is loaded
 #[derive(Component, Clone, Serialize, Deserialize, Reflect, FromReflect)]
 #[reflect(Component)]
 pub struct TerrainComponent{
+    pub size: usize,
+    pub amplitude: f64,
+    pub seed: u32,
+    pub noise: TerrainNoise,
+    pub height_map: TerrainHeightMap,
     #[reflect(ignore)]
     pub geometry: Arc<Mutex<Box<TerrainGeometry>>>,
 }
 
+crate::register_type!(TerrainComponent);
+
 impl TerrainComponent{
 
     pub fn create(size: usize) -> Self{
+        let geometry = TerrainGeometry::new(size);
         TerrainComponent{
-            geometry: Arc::new(Mutex::new(Box::new(TerrainGeometry::new(size))))
+            size,
+            amplitude: geometry.amplitude,
+            seed: geometry.seed,
+            noise: TerrainNoise::default(),
+            height_map: TerrainHeightMap::Generated,
+            geometry: Arc::new(Mutex::new(Box::new(geometry))),
         }
     }
 
+    // builds the mesh from the parameters, or from heights if the terrain was baked. heights
+    // that don't match the size are ignored
+    pub fn regenerate(&self, heights: Option<&[f32]>){
+        let mut geometry = self.geometry.lock().unwrap();
+        geometry.set_params(self.size, self.amplitude, self.seed, self.noise.clone());
+        match heights {
+            Some(heights) if geometry.generate_terrain_from_heights(heights) => (),
+            Some(heights) => {
+                log::warn!(
+                    "Terrain height map has {} heights but a size of {} needs {}. Generating it instead.",
+                    heights.len(),
+                    self.size,
+                    self.size * self.size,
+                );
+                geometry.generate_terrain();
+            },
+            None => geometry.generate_terrain(),
+        }
+    }
+
+    // keeps the current heights in the scene instead of generating them on load
+    pub fn bake_height_map(&mut self){
+        self.height_map = TerrainHeightMap::Embedded(self.geometry.lock().unwrap().baked_heights());
+    }
+
     pub fn initialize(&mut self, memory_allocator: Arc<StandardMemoryAllocator>){
         log::info!("Initializing Terrain Geometry...");
         let geometry = self.geometry.clone();//.as_ref();//unwrap();
@@ -42,20 +127,20 @@ impl TerrainComponent{
         self.geometry.lock().unwrap().initialized
     }
 
-    pub fn set_size(&self, size: usize){
-        self.geometry.clone().lock().unwrap().size = size;
+    pub fn set_size(&mut self, size: usize){
+        self.size = size;
     }
 
     pub fn get_size(&self) -> usize {
-        self.geometry.clone().lock().unwrap().size
+        self.size
     }
 
     pub fn get_amplitude(&self) -> f64 {
-        self.geometry.clone().lock().unwrap().amplitude
+        self.amplitude
     }
 
-    pub fn set_amplitude(&self, amplitude: f64){
-        self.geometry.lock().unwrap().amplitude = amplitude;
+    pub fn set_amplitude(&mut self, amplitude: f64){
+        self.amplitude = amplitude;
     }
 }
 
@@ -63,4 +148,4 @@ impl Default for TerrainComponent {
     fn default() -> Self {
         TerrainComponent::create(16)
     }
-}
\ No newline at end of file
+}
@@ -21,6 +21,8 @@ use bevy_ecs::prelude::ReflectComponent;
 #[reflect(Component)]
 pub struct TransformUiComponent;
 
+crate::register_type!(TransformUiComponent);
+
 #[derive(Component, Debug, Clone, Serialize, Deserialize, Reflect, FromReflect)]
 #[reflect(Component)]
 pub struct TransformComponent{
@@ -29,6 +31,8 @@ pub struct TransformComponent{
     pub scale: f32,
 }
 
+crate::register_type!(TransformComponent);
+
 impl TransformComponent{
     pub fn create_empty() -> Self {
         TransformComponent{
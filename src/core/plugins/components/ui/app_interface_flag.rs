@@ -13,3 +13,5 @@ use serde::{
 #[derive(Component, Clone, Serialize, Deserialize, Reflect, FromReflect, Default)]
 #[reflect(Component)]
 pub struct AppInterfaceFlag;
+
+crate::register_type!(AppInterfaceFlag);
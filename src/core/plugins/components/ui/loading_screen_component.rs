@@ -0,0 +1,11 @@
+use bevy_ecs::prelude::{ReflectComponent, Component};
+use bevy_reflect::prelude::{Reflect};
+use serde::{Serialize, Deserialize};
+
+// shows a loading window with the progress of whatever scene is loading
+#[derive(Component, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Component)]
+pub struct LoadingScreenComponent{
+    pub progress: f32,
+    pub stage: String,
+}
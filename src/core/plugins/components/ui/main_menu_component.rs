@@ -92,6 +92,22 @@ pub struct FileSubMenuComponent{
     pub current_nav_path: PathBuf,
     #[reflect(ignore)]
     pub text_entry: String,
+    #[reflect(ignore)]
+    pub save_as_window: Arc<Mutex<bool>>,
+    #[reflect(ignore)]
+    pub save_as_entry: String,
+}
+
+crate::register_type!(FileSubMenuComponent);
+
+impl FileSubMenuComponent {
+    // the new and open project windows start browsing from nav_path
+    pub fn starting_at(nav_path: PathBuf) -> Self {
+        FileSubMenuComponent{
+            current_nav_path: nav_path,
+            ..FileSubMenuComponent::default()
+        }
+    }
 }
 
 impl Default for FileSubMenuComponent {
@@ -101,6 +117,8 @@ impl Default for FileSubMenuComponent {
             open_project_window: Arc::new(Mutex::new(false)),
             current_nav_path: std::env::current_dir().unwrap(),
             text_entry: std::string::String::from(""),
+            save_as_window: Arc::new(Mutex::new(false)),
+            save_as_entry: std::string::String::from(""),
         }
     }
 }
@@ -112,7 +130,17 @@ pub struct ComponentLibraryComponent{}
 
 #[derive(Component, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Component)]
-pub struct EntityInspectorComponent{}
+pub struct EntityInspectorComponent{
+    // name typed in for saving the selected entity as a prefab
+    #[reflect(ignore)]
+    #[serde(skip)]
+    pub prefab_name: Arc<Mutex<String>>,
+}
+
+// play, pause, step and stop buttons in the editor's top panel
+#[derive(Component, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Component)]
+pub struct PlayControlsComponent{}
 
 
 #[derive(Component, Reflect, Clone, Serialize, Deserialize)]
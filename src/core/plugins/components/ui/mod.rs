@@ -1,12 +1,17 @@
 pub mod app_interface_flag;
 pub mod main_menu_component;
 pub mod scene_graph_component;
+pub mod loading_screen_component;
+pub mod project_picker_component;
 
 pub use app_interface_flag::AppInterfaceFlag;
 pub use main_menu_component::{
     FileMenuSaveComponent,
     FileSubMenuComponent,
+    PlayControlsComponent,
 };
 pub use scene_graph_component::{
     SceneGraphComponent,
-};
\ No newline at end of file
+};
+pub use loading_screen_component::LoadingScreenComponent;
+pub use project_picker_component::ProjectPickerComponent;
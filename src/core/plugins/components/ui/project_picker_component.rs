@@ -0,0 +1,17 @@
+use bevy_ecs::prelude::{ReflectComponent, Component};
+use bevy_reflect::prelude::{Reflect};
+use serde::{Serialize, Deserialize};
+
+// shows the recent projects when nothing is open so one can be picked straight away
+#[derive(Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct ProjectPickerComponent{
+    // closing the picker hides it until the idle interface is spawned again
+    pub open: bool,
+}
+
+impl Default for ProjectPickerComponent{
+    fn default() -> Self {
+        ProjectPickerComponent{ open: true }
+    }
+}
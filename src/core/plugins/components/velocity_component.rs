@@ -16,3 +16,5 @@ use bevy_ecs::prelude::ReflectComponent;
 pub struct VelocityComponent{
     pub velocity: Vector3f,
 }
+
+crate::register_type!(VelocityComponent);
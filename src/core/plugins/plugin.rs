@@ -1,6 +1,10 @@
 
 use std::any::Any;
 
+use bevy_reflect::TypeRegistry;
+
+use crate::core::scene::{MigrationRegistry, ScheduleRegistry};
+
 
 
 
@@ -15,6 +19,15 @@ pub trait Plugin: Any + Send + Sync {
     /// A callback fired immediately before the plugin is unloaded. Use this if
     /// you need to do any cleanup.
     fn on_plugin_unload(&self) {}
+    /// Add the plugin's systems and stages to the scene schedules. Called once
+    /// the plugin is loaded and applied to every scene activated after that.
+    fn register_schedules(&self, _schedules: &mut ScheduleRegistry) {}
+    /// Add migrations for scenes saved with older versions of the plugin's
+    /// components. Called once the plugin is loaded.
+    fn register_migrations(&self, _migrations: &mut MigrationRegistry) {}
+    /// Register the plugin's reflected components, resources and value types
+    /// so scenes can save and load them. Called once the plugin is loaded.
+    fn register_types(&self, _registry: &mut TypeRegistry) {}
 }
 
 
@@ -29,12 +42,12 @@ pub trait Plugin: Any + Send + Sync {
 macro_rules! declare_plugin {
     ($plugin_type:ty, $constructor:path) => {
         #[no_mangle]
-        pub extern "C" fn _plugin_create() -> *mut $crate::Plugin {
+        pub extern "C" fn _plugin_create() -> *mut dyn $crate::Plugin {
             // make sure the constructor is the correct type.
             let constructor: fn() -> $plugin_type = $constructor;
 
             let object = constructor();
-            let boxed: Box<$crate::Plugin> = Box::new(object);
+            let boxed: Box<dyn $crate::Plugin> = Box::new(object);
             Box::into_raw(boxed)
         }
     };
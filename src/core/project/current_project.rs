@@ -0,0 +1,109 @@
+use std::path::{Component, Path, PathBuf};
+
+use bevy_ecs::prelude::Resource;
+
+use crate::core::errors::EngineError;
+use crate::core::project::ProjectManifest;
+use crate::core::scene::SceneFormat;
+
+// the project the active scene was opened from and the scene file it was loaded from.
+// only there once a project has been opened
+#[derive(Resource, Clone, Debug)]
+pub struct CurrentProject{
+    pub path: PathBuf,
+    pub scene_name: String,
+    // the project's scenes are the manifest's
+    pub manifest: ProjectManifest,
+}
+
+impl CurrentProject{
+    // a project without reading its manifest
+    pub fn new(path: &str, scene_name: &str) -> Self {
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        CurrentProject::with_manifest(path, scene_name, ProjectManifest::new(&name))
+    }
+
+    // reads and validates the project's manifest. opens the manifest's default scene if no
+    // scene is asked for
+    pub fn open(path: &str, scene_name: Option<&str>) -> Result<Self, EngineError> {
+        if let Some(scene_name) = scene_name {
+            check_scene_name(scene_name)?;
+        }
+        let manifest = ProjectManifest::read(Path::new(path))?;
+        let scene_name = scene_name.map(String::from).unwrap_or_else(|| manifest.default_scene.clone());
+        Ok(CurrentProject::with_manifest(path, &scene_name, manifest))
+    }
+
+    fn with_manifest(path: &str, scene_name: &str, manifest: ProjectManifest) -> Self {
+        CurrentProject{
+            path: PathBuf::from(path),
+            scene_name: scene_file_name(scene_name),
+            manifest,
+        }
+    }
+
+    pub fn scenes_dir(&self) -> PathBuf {
+        self.path.join("scenes")
+    }
+
+    pub fn scene_path(&self, scene_name: &str) -> PathBuf {
+        self.scenes_dir().join(scene_file_name(scene_name))
+    }
+
+    // where save writes to
+    pub fn current_scene_path(&self) -> PathBuf {
+        self.scene_path(&self.scene_name)
+    }
+
+    // makes scene_name the current scene, adding it to the project's scenes if it's new. the
+    // manifest has to be written for the new scene to stay part of the project
+    pub fn register_scene(&mut self, scene_name: &str){
+        let scene_name = scene_file_name(scene_name);
+        self.manifest.add_scene(&scene_name);
+        self.scene_name = scene_name;
+    }
+
+    // plugin libraries the manifest lists, as paths that can be loaded. ones that haven't been
+    // built yet are left out
+    pub fn plugin_paths(&self) -> Vec<String> {
+        self.manifest
+            .plugins
+            .iter()
+            .map(|plugin| self.path.join(plugin))
+            .filter(|path| {
+                let built = path.is_file();
+                if !built {
+                    log::warn!("Plugin {} isn't built yet. Opening the project without it.", path.to_string_lossy());
+                }
+                built
+            })
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+// scene names without a scene extension get .ron, the source format
+pub fn scene_file_name(scene_name: &str) -> String {
+    let scene_name = scene_name.trim();
+    if SceneFormat::is_scene_file(Path::new(scene_name)) {
+        String::from(scene_name)
+    } else {
+        format!("{}.{}", scene_name, SceneFormat::Ron.extension())
+    }
+}
+
+// scene names are file names in the project's scenes directory. one with a path in it, like
+// "../x" or "/x", would be read from or written to outside the project
+pub fn check_scene_name(scene_name: &str) -> Result<(), EngineError> {
+    let scene_name = scene_name.trim();
+    let mut components = Path::new(scene_name).components();
+    let is_file_name = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+    if is_file_name && !scene_name.contains(|c: char| c == '/' || c == '\\') {
+        Ok(())
+    } else {
+        Err(EngineError::InvalidSceneName(String::from(scene_name)))
+    }
+}
@@ -0,0 +1,11 @@
+pub mod current_project;
+pub mod project_manifest;
+pub mod project_template;
+pub mod recent_projects;
+
+pub use current_project::CurrentProject;
+pub use current_project::scene_file_name;
+pub use current_project::check_scene_name;
+pub use project_manifest::ProjectManifest;
+pub use project_manifest::WindowSettings;
+pub use recent_projects::RecentProjects;
@@ -0,0 +1,213 @@
+use std::path::{Component, Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::core::errors::EngineError;
+use crate::core::project::{check_scene_name, scene_file_name};
+use crate::core::scene::SceneFormat;
+use crate::core::scene::dynamic_scene::serialize_ron;
+
+// every project has one of these at its root
+pub const PROJECT_FILE: &str = "ember.project";
+
+// the version written into new manifests and checked against when one is opened
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// how the window looks while the project is open
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings{
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    // waits for the display to present frames. off can tear but has less latency
+    pub vsync: bool,
+}
+
+impl Default for WindowSettings{
+    fn default() -> Self {
+        WindowSettings{
+            title: String::from("Ember"),
+            width: 1280,
+            height: 720,
+            fullscreen: false,
+            vsync: true,
+        }
+    }
+}
+
+// the ember.project file. paths in it are relative to the project directory and scenes are
+// file names in its scenes directory
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProjectManifest{
+    pub name: String,
+    // the engine version the project was last saved with
+    pub engine_version: String,
+    // opened when no scene is asked for
+    pub default_scene: String,
+    #[serde(default)]
+    pub scenes: Vec<String>,
+    #[serde(default)]
+    pub asset_dirs: Vec<String>,
+    // plugin libraries loaded before the project's scenes, ie the project's own crate once
+    // it's been built
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    #[serde(default)]
+    pub window: WindowSettings,
+}
+
+impl ProjectManifest{
+    // a manifest for a new project with a single default scene
+    pub fn new(name: &str) -> Self {
+        let default_scene = scene_file_name("default");
+        ProjectManifest{
+            name: String::from(name),
+            engine_version: String::from(ENGINE_VERSION),
+            default_scene: default_scene.clone(),
+            scenes: vec![default_scene],
+            asset_dirs: vec![String::from("assets")],
+            plugins: Vec::new(),
+            window: WindowSettings{
+                title: String::from(name),
+                ..WindowSettings::default()
+            },
+        }
+    }
+
+    // reads and validates the manifest in project_dir. projects made before manifests had an
+    // empty ember.project, those get one made from what's in the project
+    pub fn read(project_dir: &Path) -> Result<Self, EngineError> {
+        let path = project_dir.join(PROJECT_FILE);
+        let manifest_error = |reason: String| EngineError::ProjectManifest{
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        let text = std::fs::read_to_string(&path).map_err(|e| manifest_error(e.to_string()))?;
+        let manifest = if text.trim().is_empty() {
+            log::warn!("{} is empty. Making a manifest from the project's scenes...", path.to_string_lossy());
+            let manifest = ProjectManifest::from_existing(project_dir);
+            manifest.write(project_dir)?;
+            manifest
+        } else {
+            ron::from_str(&text).map_err(|e| manifest_error(e.to_string()))?
+        };
+        manifest.validate(project_dir).map_err(|problems| manifest_error(problems.join("; ")))?;
+        Ok(manifest)
+    }
+
+    pub fn write(&self, project_dir: &Path) -> Result<(), EngineError> {
+        let path = project_dir.join(PROJECT_FILE);
+        let manifest_error = |reason: String| EngineError::ProjectManifest{
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        let text = serialize_ron(self).map_err(|e| manifest_error(e.to_string()))?;
+        std::fs::write(&path, text).map_err(|e| manifest_error(e.to_string()))
+    }
+
+    // everything wrong with the manifest, so it can all be fixed at once
+    pub fn validate(&self, project_dir: &Path) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if self.name.trim().is_empty() {
+            problems.push(String::from("name is empty"));
+        }
+        match (parse_version(&self.engine_version), parse_version(ENGINE_VERSION)) {
+            (Some(project), Some(engine)) if project > engine => problems.push(format!(
+                "it was made with engine {}, which is newer than this one ({})",
+                self.engine_version,
+                ENGINE_VERSION,
+            )),
+            (None, _) => problems.push(format!("engine_version '{}' isn't a version like 0.2.0", self.engine_version)),
+            _ => (),
+        }
+
+        let scenes_dir = project_dir.join("scenes");
+        if !SceneFormat::is_scene_file(Path::new(&self.default_scene)) {
+            problems.push(format!("default_scene '{}' isn't a .ron or .scn file", self.default_scene));
+        } else if !self.scenes.contains(&self.default_scene) {
+            problems.push(format!("default_scene '{}' isn't in scenes", self.default_scene));
+        }
+        for scene in self.scenes.iter() {
+            if let Err(e) = check_scene_name(scene) {
+                problems.push(e.to_string());
+            } else if !scenes_dir.join(scene).is_file() {
+                problems.push(format!("scene '{}' isn't in {}", scene, scenes_dir.to_string_lossy()));
+            }
+        }
+        for dir in self.asset_dirs.iter() {
+            if !is_inside_project(dir) {
+                problems.push(format!("asset directory '{}' is outside the project", dir));
+            } else if !project_dir.join(dir).is_dir() {
+                problems.push(format!("asset directory '{}' doesn't exist", dir));
+            }
+        }
+        // plugins that haven't been built yet are skipped when the project opens
+        for plugin in self.plugins.iter() {
+            if !is_inside_project(plugin) {
+                problems.push(format!("plugin '{}' is outside the project", plugin));
+            }
+        }
+        if self.window.width == 0 || self.window.height == 0 {
+            problems.push(format!("window size {}x{} is empty", self.window.width, self.window.height));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    // makes scene_name part of the project
+    pub fn add_scene(&mut self, scene_name: &str){
+        let scene_name = scene_file_name(scene_name);
+        if !self.scenes.contains(&scene_name) {
+            self.scenes.push(scene_name);
+        }
+    }
+
+    fn from_existing(project_dir: &Path) -> Self {
+        let name = project_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("project"));
+        let mut manifest = ProjectManifest::new(&name);
+        manifest.scenes = std::fs::read_dir(project_dir.join("scenes"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|p| SceneFormat::is_scene_file(p))
+                    .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        manifest.scenes.sort();
+        if !manifest.scenes.contains(&manifest.default_scene) {
+            if let Some(first) = manifest.scenes.first() {
+                manifest.default_scene = first.clone();
+            }
+        }
+        manifest.asset_dirs.retain(|dir| project_dir.join(dir).is_dir());
+        manifest
+    }
+}
+
+// major, minor and patch. anything after a - is ignored
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.split('-').next()?;
+    let mut parts = version.trim().split('.').map(|p| p.parse::<u32>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    match parts.next() {
+        Some(_) => None,
+        None => Some(version),
+    }
+}
+
+fn is_inside_project(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
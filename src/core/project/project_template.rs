@@ -0,0 +1,169 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::errors::EngineError;
+use crate::core::project::{ProjectManifest, scene_file_name};
+use crate::core::scene::{DynamicScene, TypeRegistryResource};
+
+// points new projects at an engine checkout other than the one the editor was built from. it's
+// written into the project's Cargo.toml as it is, so a relative path is relative to the project
+pub const EMBER_PATH_VAR: &str = "EMBER_PATH";
+
+// files every new project starts with, as (path in the project, template). {{name}} in a
+// template is replaced with the value of name
+const PROJECT_TEMPLATE: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../../../templates/project/Cargo.toml.template")),
+    ("src/lib.rs", include_str!("../../../templates/project/lib.rs.template")),
+    (".gitignore", include_str!("../../../templates/project/gitignore.template")),
+];
+
+// fills in every {{name}} in template. unknown names are left as they are
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::from(template);
+    for (name, value) in values.iter() {
+        rendered = rendered.replace(&format!("{{{{{}}}}}", name), value);
+    }
+    rendered
+}
+
+// a cargo package name for the project. lowercase letters, digits and underscores
+pub fn crate_name(project_name: &str) -> String {
+    let mut name: String = project_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "game_");
+    }
+    name
+}
+
+// the plugin struct's name, ie my_game becomes MyGamePlugin
+pub fn plugin_type_name(project_name: &str) -> String {
+    let mut name: String = crate_name(project_name)
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    name.push_str("Plugin");
+    name
+}
+
+// where cargo puts the project's plugin library in a debug build
+pub fn plugin_library_path(project_name: &str) -> String {
+    format!(
+        "target/debug/{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        crate_name(project_name),
+        std::env::consts::DLL_SUFFIX,
+    )
+}
+
+// the path from one directory to another, going up out of from as far as needed. None when they
+// don't share a root, like paths on different drives
+pub fn relative_path(from: &Path, to: &Path) -> Option<PathBuf> {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    if from.first() != to.first() {
+        return None;
+    }
+    let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for component in to[common..].iter() {
+        path.push(component.as_os_str());
+    }
+    Some(path)
+}
+
+// how the project's crate finds the engine. EMBER_PATH if it's set, otherwise the checkout the
+// editor was built from, relative to the project so the project still builds when it's moved
+// along with the engine or checked out next to it somewhere else
+pub fn engine_dependency_path(project_dir: &Path) -> String {
+    if let Some(path) = std::env::var_os(EMBER_PATH_VAR) {
+        return path.to_string_lossy().replace('\\', "/");
+    }
+    let engine_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let canonical = |dir: &Path| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let path = match relative_path(&canonical(project_dir), &canonical(engine_dir)) {
+        Some(path) => path,
+        None => {
+            log::warn!(
+                "{} can't be reached from {} with a relative path. Set {} to change where the project looks for the engine.",
+                engine_dir.to_string_lossy(),
+                project_dir.to_string_lossy(),
+                EMBER_PATH_VAR,
+            );
+            engine_dir.to_path_buf()
+        }
+    };
+    path.to_string_lossy().replace('\\', "/")
+}
+
+// makes a project that's ready to open and build. the manifest, an empty scene, the asset
+// folders, and a plugin crate that depends on the engine (see engine_dependency_path). nothing
+// is downloaded, the crate's dependencies are fetched on its first build. fails if the project
+// folder exists
+pub fn create_project(project_path: &str, scene_name: &str) -> Result<ProjectManifest, EngineError> {
+    let creation_error = |reason: String| EngineError::ProjectCreation{
+        path: String::from(project_path),
+        reason,
+    };
+
+    let project_dir = Path::new(project_path);
+    std::fs::create_dir_all(project_dir.parent().unwrap_or_else(|| Path::new(".")))
+        .map_err(|e| creation_error(e.to_string()))?;
+    std::fs::create_dir(project_dir).map_err(|e| creation_error(e.to_string()))?;
+    let name = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut manifest = ProjectManifest::new(&name);
+    manifest.default_scene = scene_file_name(scene_name);
+    manifest.scenes = vec![manifest.default_scene.clone()];
+    manifest.plugins = vec![plugin_library_path(&name)];
+
+    let scenes_dir = project_dir.join("scenes");
+    std::fs::create_dir(&scenes_dir).map_err(|e| creation_error(e.to_string()))?;
+    for dir in manifest.asset_dirs.iter() {
+        std::fs::create_dir_all(project_dir.join(dir)).map_err(|e| creation_error(e.to_string()))?;
+    }
+
+    let type_registry = TypeRegistryResource::default().0;
+    let default_scene = scenes_dir.join(&manifest.default_scene).to_string_lossy().to_string();
+    DynamicScene::default()
+        .write_to_file(&default_scene, &type_registry)
+        .map_err(|e| creation_error(format!("couldn't create default scene: {}", e)))?;
+
+    let crate_name = crate_name(&name);
+    let plugin_type = plugin_type_name(&name);
+    let ember_path = engine_dependency_path(project_dir);
+    let values = [
+        ("project_name", name.as_str()),
+        ("crate_name", crate_name.as_str()),
+        ("plugin_type", plugin_type.as_str()),
+        ("ember_path", ember_path.as_str()),
+    ];
+    for (path, template) in PROJECT_TEMPLATE.iter() {
+        let path = project_dir.join(path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| creation_error(e.to_string()))?;
+        }
+        std::fs::write(&path, render_template(template, &values))
+            .map_err(|e| creation_error(format!("couldn't write {}: {}", path.to_string_lossy(), e)))?;
+    }
+
+    manifest
+        .write(project_dir)
+        .map_err(|e| creation_error(format!("couldn't create project file: {}", e)))?;
+    Ok(manifest)
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy_ecs::prelude::Resource;
+use serde::{Serialize, Deserialize};
+
+use crate::core::errors::EngineError;
+use crate::core::project::project_manifest::PROJECT_FILE;
+use crate::core::scene::dynamic_scene::serialize_ron;
+
+// the file in the user's config directory the list is kept in
+pub const RECENT_PROJECTS_FILE: &str = "recent_projects.ron";
+
+// how many projects are remembered
+pub const MAX_RECENT_PROJECTS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject{
+    pub path: PathBuf,
+    pub name: String,
+    // seconds since the unix epoch
+    pub opened: u64,
+}
+
+// projects opened in the editor, most recent first. shared between sessions through a file in
+// the user's config directory
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecentProjects{
+    pub projects: Vec<RecentProject>,
+}
+
+impl RecentProjects{
+    // the list from the user's config directory. a missing or broken file is an empty list.
+    // projects that are gone are dropped from the file too
+    pub fn load() -> Self {
+        let path = match recent_projects_path() {
+            Some(p) => p,
+            None => {
+                log::warn!("No config directory. Recent projects won't be remembered.");
+                return RecentProjects::default();
+            }
+        };
+        if !path.exists() {
+            return RecentProjects::default();
+        }
+        let mut recent = match RecentProjects::parse(&path) {
+            Ok(recent) => recent,
+            Err(e) => {
+                log::warn!("{}", e);
+                return RecentProjects::default();
+            }
+        };
+        if recent.prune() {
+            if let Err(e) = recent.write(&path) {
+                log::warn!("{}", e);
+            }
+        }
+        recent
+    }
+
+    // writes the list to the user's config directory
+    pub fn save(&self) -> Result<(), EngineError> {
+        match recent_projects_path() {
+            Some(path) => self.write(&path),
+            None => Ok(()),
+        }
+    }
+
+    // reads the list from path, dropping projects that aren't there anymore
+    pub fn read(path: &Path) -> Result<Self, EngineError> {
+        let mut recent = RecentProjects::parse(path)?;
+        recent.prune();
+        Ok(recent)
+    }
+
+    fn parse(path: &Path) -> Result<Self, EngineError> {
+        let recent_error = |reason: String| EngineError::RecentProjects{
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| recent_error(e.to_string()))?;
+        ron::from_str(&text).map_err(|e| recent_error(e.to_string()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), EngineError> {
+        let recent_error = |reason: String| EngineError::RecentProjects{
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| recent_error(e.to_string()))?;
+        }
+        let text = serialize_ron(self).map_err(|e| recent_error(e.to_string()))?;
+        std::fs::write(path, text).map_err(|e| recent_error(e.to_string()))
+    }
+
+    // moves the project to the front of the list, opened now
+    pub fn record(&mut self, path: &Path, name: &str){
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.projects.retain(|p| p.path != path);
+        self.projects.insert(0, RecentProject{
+            path,
+            name: String::from(name),
+            opened: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        });
+        // pruned first so projects that are gone don't take up places in the list
+        self.prune();
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    // drops projects that were moved or deleted. true if any were
+    pub fn prune(&mut self) -> bool {
+        let count = self.projects.len();
+        self.projects.retain(|p| p.path.join(PROJECT_FILE).is_file());
+        self.projects.len() != count
+    }
+
+    pub fn most_recent(&self) -> Option<&RecentProject> {
+        self.projects.first()
+    }
+}
+
+// $XDG_CONFIG_HOME/ember, or ~/.config/ember when that isn't set
+pub fn config_dir() -> Option<PathBuf> {
+    let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        // the spec says relative paths are to be ignored
+        .filter(|p| p.is_absolute());
+    let config_home = match xdg_config_home {
+        Some(p) => p,
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("ember"))
+}
+
+pub fn recent_projects_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(RECENT_PROJECTS_FILE))
+}
+
+// how long ago a project was opened, ie "5 minutes ago"
+pub fn opened_ago(opened: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let seconds = now.saturating_sub(opened);
+    let (count, unit) = match seconds {
+        0..=59 => return String::from("just now"),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::core::errors::EngineError;
+use crate::core::rendering::geometries::Vertex;
+
+// triangles as vertex positions and indices into them, three per triangle
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeshData{
+    pub positions: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData{
+    pub fn new(vertices: &[Vertex], indices: &[u32]) -> Self {
+        MeshData{
+            positions: vertices.iter().map(|v| v.position).collect(),
+            indices: indices.to_vec(),
+        }
+    }
+
+    pub fn vertices(&self) -> Vec<Vertex> {
+        self.positions.iter().map(|p| Vertex{ position: *p }).collect()
+    }
+
+    // an index past the last vertex would read outside the vertex buffer
+    pub fn validate(&self) -> Result<(), String> {
+        if self.positions.is_empty() || self.indices.is_empty() {
+            return Err(String::from("mesh is empty"));
+        }
+        match self.indices.iter().find(|i| **i as usize >= self.positions.len()) {
+            Some(i) => Err(format!("index {} is past the last of {} vertices", i, self.positions.len())),
+            None => Ok(()),
+        }
+    }
+}
+
+// reads a mesh from a .ron file holding MeshData or a wavefront .obj file
+pub fn load_mesh_file(path: &str) -> Result<MeshData, EngineError> {
+    let mesh_error = |reason: String| EngineError::Mesh{ path: String::from(path), reason };
+    let text = std::fs::read_to_string(path).map_err(|e| mesh_error(e.to_string()))?;
+    let mesh = match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("ron") => ron::from_str(&text).map_err(|e| mesh_error(e.to_string()))?,
+        Some("obj") => parse_obj(&text).map_err(mesh_error)?,
+        _ => return Err(mesh_error(String::from("meshes have to be .ron or .obj files"))),
+    };
+    mesh.validate().map_err(mesh_error)?;
+    Ok(mesh)
+}
+
+// the positions and faces of an obj file. faces with more than three vertices are split into a
+// fan of triangles. normals, texture coordinates, groups and materials are skipped
+pub fn parse_obj(text: &str) -> Result<MeshData, String> {
+    let mut mesh = MeshData::default();
+    for (line_number, line) in text.lines().enumerate() {
+        let line_error = |reason: &str| format!("line {}: {}", line_number + 1, reason);
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => {
+                let position: Vec<f32> = parts
+                    .take(3)
+                    .map(|p| p.parse::<f32>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| line_error("vertex positions have to be numbers"))?;
+                if position.len() != 3 {
+                    return Err(line_error("vertices need x, y and z"));
+                }
+                mesh.positions.push([position[0], position[1], position[2]]);
+            },
+            Some("f") => {
+                let mut face = Vec::new();
+                for part in parts {
+                    // v, v/vt, v//vn or v/vt/vn. only the position matters
+                    let index = part
+                        .split('/')
+                        .next()
+                        .and_then(|i| i.parse::<i64>().ok())
+                        .ok_or_else(|| line_error("face indices have to be numbers"))?;
+                    // obj indices start at 1, negative ones count back from the last vertex
+                    let index = if index < 0 {
+                        mesh.positions.len() as i64 + index
+                    } else {
+                        index - 1
+                    };
+                    if index < 0 {
+                        return Err(line_error("face index is out of range"));
+                    }
+                    face.push(index as u32);
+                }
+                if face.len() < 3 {
+                    return Err(line_error("faces need at least three vertices"));
+                }
+                for i in 1..face.len() - 1 {
+                    mesh.indices.extend([face[0], face[i], face[i + 1]]);
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok(mesh)
+}
@@ -1,5 +1,7 @@
 pub mod geometry_primitives;
 pub mod terrain;
+pub mod mesh;
 
 pub use geometry_primitives::Vertex;
-pub use terrain::TerrainGeometry;
\ No newline at end of file
+pub use terrain::TerrainGeometry;
+pub use mesh::MeshData;
\ No newline at end of file
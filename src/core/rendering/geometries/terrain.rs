@@ -6,12 +6,14 @@ use std::borrow::Borrow;
 
 use crate::core::rendering::geometries::Vertex;
 use crate::core::plugins::components::GeometryComponent;
+use crate::core::plugins::components::terrain_component::TerrainNoise;
+use crate::core::errors::EngineError;
 
 use vulkano::buffer::CpuAccessibleBuffer;
 use vulkano::buffer::BufferUsage;
 use vulkano::memory::allocator::StandardMemoryAllocator;
 
-use noise::{NoiseFn, OpenSimplex};
+use noise::{NoiseFn, OpenSimplex, Seedable};
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize)]
@@ -22,6 +24,8 @@ pub struct TerrainGeometry{
     pub size: usize,
     pub amplitude: f64,
     pub seed: u32,
+    #[serde(default)]
+    pub noise: TerrainNoise,
     #[serde(skip, default="TerrainGeometry::default_noise_fn")]
     pub noise_fn: Box<dyn NoiseFn<[f64; 2]> + Send + Sync>,
     #[serde(skip, default="GeometryComponent::default_vertex_buffer")]
@@ -39,7 +43,8 @@ impl TerrainGeometry{
             height_map: Vec::new(),
             size: size,
             amplitude: 10.0,
-            seed: 1,
+            seed: 0,
+            noise: TerrainNoise::default(),
             noise_fn: Box::new(OpenSimplex::new()),
             vertex_buffer: None,
             index_buffer: None,
@@ -53,26 +58,80 @@ impl TerrainGeometry{
         self.generate_terrain();
     }
 
+    // takes the generation parameters from a terrain component. the noise function is rebuilt
+    // from the seed, so a custom one has to be set again afterwards
+    pub fn set_params(&mut self, size: usize, amplitude: f64, seed: u32, noise: TerrainNoise){
+        self.size = size;
+        self.amplitude = amplitude;
+        self.seed = seed;
+        self.noise = noise;
+        self.noise_fn = Box::new(OpenSimplex::new().set_seed(seed));
+    }
+
     pub fn generate_terrain(&mut self){
+        let heights = self.noise_heights();
+        self.build_mesh(&heights);
+    }
+
+    // builds the mesh from baked heights, size * size of them row by row. false if there's the
+    // wrong number of them
+    pub fn generate_terrain_from_heights(&mut self, heights: &[f32]) -> bool {
+        if heights.len() != self.size * self.size {
+            return false;
+        }
+        self.build_mesh(heights);
+        true
+    }
+
+    // the heights the mesh was last built from, row by row
+    pub fn baked_heights(&self) -> Vec<f32> {
+        self.height_map.iter().flatten().map(|h| *h as f32).collect()
+    }
+
+    // octaves of noise, each at lacunarity times the frequency and persistence times the
+    // amplitude of the one before
+    fn noise_heights(&self) -> Vec<f32> {
+        let size = self.size;
+        let noise_fn: &(dyn NoiseFn<[f64; 2]> + Send + Sync) = self.noise_fn.borrow();
+        let mut heights = Vec::with_capacity(size * size);
+        for x in 0..size {
+            for z in 0..size {
+                let mut frequency = self.noise.frequency;
+                let mut amplitude = 1.0;
+                let mut noise = 0.0;
+                for _ in 0..self.noise.octaves.max(1) {
+                    noise += noise_fn.get([x as f64 * frequency, z as f64 * frequency]) * amplitude;
+                    frequency *= self.noise.lacunarity;
+                    amplitude *= self.noise.persistence;
+                }
+                heights.push((noise * self.amplitude) as f32);
+            }
+        }
+        heights
+    }
+
+    fn build_mesh(&mut self, heights: &[f32]){
         self.height_map.clear();
         let size = self.size as u32;
         self.vertices.clear();
         self.indices.clear();
-        let noise_fn: &(dyn NoiseFn<[f64; 2]> + Send + Sync) = self.noise_fn.borrow();
-        let mut i = 0;
         for x in 0..size {
+            let mut row = Vec::with_capacity(size as usize);
             for z in 0..size {
-                let noise = noise_fn.get([x as f64, z as f64]);
-                let y = (noise * self.amplitude) as f32;
+                let y = heights[(x * size + z) as usize];
+                row.push(y as f64);
                 self.vertices.push(
                     Vertex{
-                        position: [x as f32, y as f32, z as f32]
+                        position: [x as f32, y, z as f32]
                     }
                 );
-                i = i + 1;
             }
+            self.height_map.push(row);
         }
 
+        if size < 2 {
+            return;
+        }
         for y in 0..(size-1) {
             for x in 0..(size-1) {
                 let ix = (y * size + x) as u32;
@@ -136,11 +195,28 @@ impl Default for TerrainGeometry {
             height_map: Vec::new(),
             size: 128,
             amplitude: 1.0,
-            seed: 1,
+            seed: 0,
+            noise: TerrainNoise::default(),
             noise_fn: Box::new(OpenSimplex::new()),
             vertex_buffer: None,
             index_buffer: None,
             initialized: false
         }
     }
-}
\ No newline at end of file
+}
+
+// baked heights saved outside the scene, for terrain too big to embed
+pub fn write_height_map(path: &str, heights: &[f32]) -> Result<(), EngineError> {
+    let height_map_error = |reason: String| EngineError::HeightMap{ path: String::from(path), reason };
+    let bytes = bincode::serialize(heights).map_err(|e| height_map_error(e.to_string()))?;
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(dir).map_err(|e| height_map_error(e.to_string()))?;
+    }
+    std::fs::write(path, bytes).map_err(|e| height_map_error(e.to_string()))
+}
+
+pub fn read_height_map(path: &str) -> Result<Vec<f32>, EngineError> {
+    let height_map_error = |reason: String| EngineError::HeightMap{ path: String::from(path), reason };
+    let bytes = std::fs::read(path).map_err(|e| height_map_error(e.to_string()))?;
+    bincode::deserialize(&bytes).map_err(|e| height_map_error(e.to_string()))
+}
@@ -7,13 +7,16 @@ use crate::core::scene::{
 };
 use bevy_ecs::{
     entity::EntityMap,
-    reflect::{ReflectComponent, ReflectMapEntities},
+    reflect::{ReflectComponent, ReflectResource},
     world::World,
 };
 use bevy_reflect::{Reflect, TypeRegistryArc, TypeUuid};
+use crate::core::errors::EngineError;
 use serde::Serialize;
 
 use super::TypeRegistryResource;
+use super::entity_remap::map_entity_references;
+use super::scene_format::{serialize_scene, serialize_binary};
 
 /// A collection of serializable dynamic entities, each with its own run-time defined set of components.
 /// To spawn a dynamic scene, you can use either:
@@ -26,6 +29,8 @@ use super::TypeRegistryResource;
 #[uuid = "749479b1-fb8c-4ff8-a775-623aa76014f5"]
 pub struct DynamicScene {
     pub entities: Vec<DynamicEntity>,
+    /// Scene-wide state saved from the world's resources.
+    pub resources: Vec<Box<dyn Reflect>>,
 }
 
 /// A reflection-powered serializable representation of an entity and its components.
@@ -44,15 +49,17 @@ impl DynamicScene {
             DynamicSceneBuilder::from_world_with_type_registry(world, type_registry.clone());
 
         builder.extract_entities(world.iter_entities());
+        builder.extract_resources();
 
         builder.build()
     }
 
-    /// Write the dynamic entities and their corresponding components to the given world.
+    /// Write the dynamic entities and their corresponding components, then the scene's
+    /// resources, to the given world.
     ///
-    /// This method will return a [`SceneSpawnError`] if a type either is not registered
-    /// in the provided [`TypeRegistryArc`] resource, or doesn't reflect the
-    /// [`Component`](bevy_ecs::component::Component) trait.
+    /// Components whose type either is not registered in the provided [`TypeRegistryArc`]
+    /// resource, or doesn't reflect the [`Component`](bevy_ecs::component::Component) trait,
+    /// are skipped with a warning.
     pub fn write_to_world_with(
         &self,
         world: &mut World,
@@ -61,41 +68,83 @@ impl DynamicScene {
     ) -> Result<(), SceneSpawnError> {
         let type_registry = type_registry.read();
 
+        // spawn everything first so references to entities further down the file can be mapped
         for scene_entity in &self.entities {
             // Fetch the entity with the given entity id from the `entity_map`
             // or spawn a new entity with a transiently unique id if there is
             // no corresponding entry.
-            let entity = *entity_map
+            entity_map
                 .entry(bevy_ecs::entity::Entity::from_raw(scene_entity.entity))
                 .or_insert_with(|| world.spawn_empty().id());
+        }
+
+        for scene_entity in &self.entities {
+            let entity = entity_map
+                .get(bevy_ecs::entity::Entity::from_raw(scene_entity.entity))
+                .unwrap();
 
             // Apply/ add each component to the given entity.
             for component in &scene_entity.components {
-                let registration = type_registry
-                    .get_with_name(component.type_name())
-                    .ok_or_else(|| SceneSpawnError::UnregisteredType {
-                        type_name: component.type_name().to_string(),
-                    })?;
-                let reflect_component =
-                    registration.data::<ReflectComponent>().ok_or_else(|| {
-                        SceneSpawnError::UnregisteredComponent {
+                // one component that can't be spawned shouldn't lose the rest of the scene
+                let registration = match type_registry.get_with_name(component.type_name()) {
+                    Some(r) => r,
+                    None => {
+                        log::warn!("{}", SceneSpawnError::UnregisteredType {
                             type_name: component.type_name().to_string(),
-                        }
-                    })?;
+                        });
+                        continue;
+                    }
+                };
+                let reflect_component = match registration.data::<ReflectComponent>() {
+                    Some(r) => r,
+                    None => {
+                        log::warn!("{}", SceneSpawnError::UnregisteredComponent {
+                            type_name: component.type_name().to_string(),
+                        });
+                        continue;
+                    }
+                };
+
+                // entity references in the file point at ids from the world it was saved from
+                let mut component = component.clone_value();
+                if !map_entity_references(&mut *component, entity_map) {
+                    // e.g. a parent that wasn't saved. better left off than pointing at
+                    // whatever entity has that id here
+                    log::warn!(
+                        "Skipping {} on entity {}. It references an entity that isn't in the scene.",
+                        component.type_name(),
+                        scene_entity.entity,
+                    );
+                    continue;
+                }
 
                 // If the entity already has the given component attached,
                 // just apply the (possibly) new value, otherwise add the
                 // component to the entity.
-                reflect_component.apply_or_insert(world, entity, &**component);
+                reflect_component.apply_or_insert(world, entity, &*component);
             }
         }
 
-        for registration in type_registry.iter() {
-            if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                map_entities_reflect
-                    .map_entities(world, entity_map)
-                    .unwrap();
+        for resource in &self.resources {
+            let reflect_resource = match type_registry
+                .get_with_name(resource.type_name())
+                .and_then(|registration| registration.data::<ReflectResource>())
+            {
+                Some(r) => r,
+                None => {
+                    log::warn!("Skipping resource {}. It isn't registered with #[reflect(Resource)].", resource.type_name());
+                    continue;
+                }
+            };
+            let mut resource = resource.clone_value();
+            if !map_entity_references(&mut *resource, entity_map) {
+                log::warn!(
+                    "Skipping resource {}. It references an entity that isn't in the scene.",
+                    resource.type_name(),
+                );
+                continue;
             }
+            reflect_resource.apply_or_insert(world, &*resource);
         }
 
         Ok(())
@@ -103,9 +152,9 @@ impl DynamicScene {
 
     /// Write the dynamic entities and their corresponding components to the given world.
     ///
-    /// This method will return a [`SceneSpawnError`] if a type either is not registered
-    /// in the world's [`TypeRegistryArc`] resource, or doesn't reflect the
-    /// [`Component`](bevy_ecs::component::Component) trait.
+    /// Components whose type either is not registered in the world's [`TypeRegistryArc`]
+    /// resource, or doesn't reflect the [`Component`](bevy_ecs::component::Component) trait,
+    /// are skipped with a warning.
     pub fn write_to_world(
         &self,
         world: &mut World,
@@ -121,11 +170,22 @@ impl DynamicScene {
         serialize_ron(SceneSerializer::new(self, registry))
     }
 
-    pub fn write_to_file(&self, file_name: &str, type_registry: &TypeRegistryArc){
-        let ronald = self.serialize_ron(type_registry).expect("error in serialization");
-        let mut buffer = File::create(file_name).expect("Couldn't create file");
-        buffer.write(ronald.as_bytes()).expect("Couldn't write to file.");
-        
+    /// Serialize this dynamic scene into the length prefixed binary scene format.
+    pub fn serialize_binary(&self, registry: &TypeRegistryArc) -> Result<Vec<u8>, bincode::Error> {
+        serialize_binary(self, registry)
+    }
+
+    // writes ron or binary depending on the file's extension
+    pub fn write_to_file(&self, file_name: &str, type_registry: &TypeRegistryArc) -> Result<(), EngineError> {
+        let bytes = serialize_scene(file_name, self, type_registry)?;
+        let mut buffer = File::create(file_name).map_err(|e| EngineError::SceneWrite{
+            path: String::from(file_name),
+            reason: e.to_string(),
+        })?;
+        buffer.write_all(&bytes).map_err(|e| EngineError::SceneWrite{
+            path: String::from(file_name),
+            reason: e.to_string(),
+        })
     }
 }
 
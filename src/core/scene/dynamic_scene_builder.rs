@@ -1,10 +1,12 @@
 use crate::core::scene::dynamic_scene::{DynamicEntity, DynamicScene};
 use bevy_reflect::TypeRegistryArc;
-use bevy_ecs::{prelude::Entity, reflect::ReflectComponent, world::World};
+use bevy_ecs::{prelude::Entity, reflect::{ReflectComponent, ReflectResource}, world::World};
+use bevy_reflect::Reflect;
 use bevy_utils::default;
 use std::collections::BTreeMap;
 
 use super::TypeRegistryResource;
+use super::scene_resources::is_scene_resource;
 
 /// A [`DynamicScene`] builder, used to build a scene from a [`World`] by extracting some entities.
 ///
@@ -34,6 +36,7 @@ use super::TypeRegistryResource;
 /// ```
 pub struct DynamicSceneBuilder<'w> {
     entities: BTreeMap<u32, DynamicEntity>,
+    resources: BTreeMap<String, Box<dyn Reflect>>,
     type_registry: TypeRegistryArc,
     world: &'w World,
 }
@@ -44,6 +47,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn from_world(world: &'w World) -> Self {
         Self {
             entities: default(),
+            resources: default(),
             type_registry: world.resource::<TypeRegistryResource>().0.clone(),
             world,
         }
@@ -54,6 +58,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn from_world_with_type_registry(world: &'w World, type_registry: TypeRegistryArc) -> Self {
         Self {
             entities: default(),
+            resources: default(),
             type_registry,
             world,
         }
@@ -63,6 +68,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn build(self) -> DynamicScene {
         DynamicScene {
             entities: self.entities.into_values().collect(),
+            resources: self.resources.into_values().collect(),
         }
     }
 
@@ -133,4 +139,24 @@ impl<'w> DynamicSceneBuilder<'w> {
         drop(type_registry);
         self
     }
+
+    /// Extract every resource in the builder's [`World`] that is saved with scenes, see
+    /// [`SaveWithScene`](super::SaveWithScene).
+    ///
+    /// Resources are stored in order of their type name, so saving the same world twice gives
+    /// the same file.
+    pub fn extract_resources(&mut self) -> &mut Self {
+        let type_registry = self.type_registry.read();
+
+        for registration in type_registry.iter().filter(|r| is_scene_resource(r)) {
+            let reflect_resource = registration.data::<ReflectResource>().unwrap();
+            if let Some(resource) = reflect_resource.reflect(self.world) {
+                self.resources
+                    .insert(String::from(registration.type_name()), resource.clone_value());
+            }
+        }
+
+        drop(type_registry);
+        self
+    }
 }
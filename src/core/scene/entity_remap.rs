@@ -0,0 +1,82 @@
+use bevy_ecs::entity::{Entity, EntityMap};
+use bevy_reflect::{DynamicList, Reflect, ReflectMut};
+
+// rewrites every Entity inside a reflected value from its id in a scene file to the entity it
+// was spawned as. works on any component, so hierarchy components and user components holding
+// entity references are remapped the same way.
+// references to entities that aren't in the map are dropped from lists. anywhere else they
+// can't be dropped, so false is returned and the caller decides what to do with the value
+pub fn map_entity_references(value: &mut dyn Reflect, entity_map: &EntityMap) -> bool {
+    let mut mapped = true;
+    match value.reflect_mut() {
+        ReflectMut::Struct(s) => {
+            for i in 0..s.field_len() {
+                if let Some(field) = s.field_at_mut(i) {
+                    mapped &= map_entity_references(field, entity_map);
+                }
+            }
+        },
+        ReflectMut::TupleStruct(s) => {
+            for i in 0..s.field_len() {
+                if let Some(field) = s.field_mut(i) {
+                    mapped &= map_entity_references(field, entity_map);
+                }
+            }
+        },
+        ReflectMut::Tuple(t) => {
+            for i in 0..t.field_len() {
+                if let Some(field) = t.field_mut(i) {
+                    mapped &= map_entity_references(field, entity_map);
+                }
+            }
+        },
+        ReflectMut::Enum(e) => {
+            for i in 0..e.field_len() {
+                if let Some(field) = e.field_at_mut(i) {
+                    mapped &= map_entity_references(field, entity_map);
+                }
+            }
+        },
+        ReflectMut::Array(a) => {
+            for i in 0..a.len() {
+                if let Some(item) = a.get_mut(i) {
+                    mapped &= map_entity_references(item, entity_map);
+                }
+            }
+        },
+        ReflectMut::Map(m) => {
+            for i in 0..m.len() {
+                if let Some((_, item)) = m.get_at_mut(i) {
+                    mapped &= map_entity_references(item, entity_map);
+                }
+            }
+        },
+        ReflectMut::List(l) => {
+            let mut kept = DynamicList::default();
+            let mut dropped = false;
+            for i in 0..l.len() {
+                if let Some(item) = l.get_mut(i) {
+                    if map_entity_references(item, entity_map) {
+                        kept.push_box(item.clone_value());
+                    } else {
+                        dropped = true;
+                    }
+                }
+            }
+            if dropped && l.set(Box::new(kept)).is_err() {
+                // not a dynamic list, so the stale references have to stay
+                mapped = false;
+            }
+        },
+        ReflectMut::Value(v) => {
+            if let Some(entity) = v.downcast_mut::<Entity>() {
+                // scene files key entities by index only, the saved generation means nothing here
+                match entity_map.get(Entity::from_raw(entity.index())) {
+                    Ok(new_entity) => *entity = new_entity,
+                    Err(_) => mapped = false,
+                }
+            }
+        },
+    }
+    mapped
+}
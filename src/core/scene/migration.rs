@@ -0,0 +1,171 @@
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+
+use bevy_ecs::prelude::Resource;
+use bevy_reflect::TypeRegistry;
+use serde::Serialize;
+
+// version of the scene file layout itself. bump it when the layout in serialization.rs changes.
+// 2 added resources, 3 length prefixed every component in binary scenes
+pub const SCENE_FORMAT_VERSION: u32 = 3;
+
+// type data holding a component's schema version. scene files record the version of every
+// component they contain, so bump it whenever a component's fields change and register a
+// migration from the old version. types without it are version 0
+#[derive(Clone, Copy, Debug)]
+pub struct SchemaVersion(pub u32);
+
+pub fn set_schema_version<T: 'static>(registry: &mut TypeRegistry, version: u32){
+    match registry.get_mut(TypeId::of::<T>()) {
+        Some(registration) => registration.insert(SchemaVersion(version)),
+        None => log::error!(
+            "Can't set the schema version of {}. It isn't registered.",
+            std::any::type_name::<T>(),
+        ),
+    }
+}
+
+pub fn schema_version(registry: &TypeRegistry, type_name: &str) -> u32 {
+    registry
+        .get_with_name(type_name)
+        .and_then(|registration| registration.data::<SchemaVersion>())
+        .map_or(0, |version| version.0)
+}
+
+#[derive(Clone, Debug)]
+enum FieldMigration{
+    Rename{
+        from: String,
+        to: String,
+    },
+    // only fills the field in if the file doesn't have it
+    Default{
+        field: String,
+        value: ron::Value,
+    },
+    Remove{
+        field: String,
+    },
+}
+
+// how to read scene files written by older versions of the engine. field migrations are tagged
+// with the schema version they upgrade to and run in order on components saved with an older
+// version, before the component is deserialized
+#[derive(Resource, Clone, Default)]
+pub struct MigrationRegistry{
+    type_renames: HashMap<String, String>,
+    removed_types: HashSet<String>,
+    field_migrations: HashMap<String, Vec<(u32, FieldMigration)>>,
+}
+
+impl MigrationRegistry{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // components saved as old_name are loaded as new_name
+    pub fn rename_type(&mut self, old_name: &str, new_name: &str) -> &mut Self {
+        self.type_renames.insert(String::from(old_name), String::from(new_name));
+        self
+    }
+
+    // components that don't exist anymore. they're skipped on load without a warning
+    pub fn remove_type(&mut self, type_name: &str) -> &mut Self {
+        self.removed_types.insert(String::from(type_name));
+        self
+    }
+
+    pub fn rename_field(&mut self, type_name: &str, version: u32, from: &str, to: &str) -> &mut Self {
+        self.push(type_name, version, FieldMigration::Rename{
+            from: String::from(from),
+            to: String::from(to),
+        })
+    }
+
+    pub fn default_field<T: Serialize>(&mut self, type_name: &str, version: u32, field: &str, value: T) -> &mut Self {
+        let value = ron::to_string(&value)
+            .map_err(|e| e.to_string())
+            .and_then(|s| ron::from_str::<ron::Value>(&s).map_err(|e| e.to_string()));
+        let value = match value {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Can't use the default for {}.{}: {}", type_name, field, e);
+                return self;
+            }
+        };
+        self.push(type_name, version, FieldMigration::Default{
+            field: String::from(field),
+            value,
+        })
+    }
+
+    pub fn remove_field(&mut self, type_name: &str, version: u32, field: &str) -> &mut Self {
+        self.push(type_name, version, FieldMigration::Remove{
+            field: String::from(field),
+        })
+    }
+
+    // follows renames to the type's current name
+    pub fn resolve_type_name(&self, type_name: &str) -> String {
+        let mut name = String::from(type_name);
+        // a rename cycle would loop forever, so give up after one pass over every rename
+        for _ in 0..=self.type_renames.len() {
+            match self.type_renames.get(&name) {
+                Some(new_name) => name = new_name.clone(),
+                None => break,
+            }
+        }
+        name
+    }
+
+    pub fn is_removed(&self, type_name: &str) -> bool {
+        self.removed_types.contains(type_name)
+    }
+
+    pub fn needs_migration(&self, type_name: &str, saved_version: u32) -> bool {
+        self.field_migrations
+            .get(type_name)
+            .map_or(false, |migrations| migrations.iter().any(|(v, _)| *v > saved_version))
+    }
+
+    // upgrades a component saved with saved_version. value is the component's fields
+    pub fn migrate(&self, type_name: &str, saved_version: u32, value: &mut ron::Value){
+        let migrations = match self.field_migrations.get(type_name) {
+            Some(m) => m,
+            None => return,
+        };
+        let fields = match value {
+            ron::Value::Map(fields) => fields,
+            _ => {
+                log::warn!("Can't migrate {}. It isn't a struct.", type_name);
+                return;
+            }
+        };
+        for (_, migration) in migrations.iter().filter(|(v, _)| *v > saved_version) {
+            match migration {
+                FieldMigration::Rename{from, to} => {
+                    if let Some(field) = fields.remove(&ron::Value::String(from.clone())) {
+                        fields.insert(ron::Value::String(to.clone()), field);
+                    }
+                },
+                FieldMigration::Default{field, value} => {
+                    let key = ron::Value::String(field.clone());
+                    if fields.get(&key).is_none() {
+                        fields.insert(key, value.clone());
+                    }
+                },
+                FieldMigration::Remove{field} => {
+                    fields.remove(&ron::Value::String(field.clone()));
+                },
+            }
+        }
+    }
+
+    fn push(&mut self, type_name: &str, version: u32, migration: FieldMigration) -> &mut Self {
+        let migrations = self.field_migrations.entry(String::from(type_name)).or_default();
+        migrations.push((version, migration));
+        // stable, so migrations to the same version run in the order they were added
+        migrations.sort_by_key(|(v, _)| *v);
+        self
+    }
+}
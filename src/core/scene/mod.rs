@@ -3,6 +3,16 @@ pub mod scene_macros;
 pub mod serialization;
 pub mod dynamic_scene;
 pub mod dynamic_scene_builder;
+pub mod scene_snapshot;
+pub mod schedule_registry;
+pub mod entity_remap;
+pub mod scene_format;
+pub mod migration;
+pub mod prefab;
+pub mod scene_resources;
+pub mod type_registration;
+pub mod scene_diff;
+pub mod scene_watcher;
 
 pub use scene::Scene;
 pub use scene::Active;
@@ -13,4 +23,12 @@ pub use scene::TypeRegistryResource;
 pub use dynamic_scene::DynamicScene;
 pub use dynamic_scene_builder::DynamicSceneBuilder;
 pub use serialization::SceneDeserializer;
+pub use scene_snapshot::SceneSnapshot;
+pub use schedule_registry::ScheduleRegistry;
+pub use schedule_registry::SceneSchedule;
+pub use scene_format::SceneFormat;
+pub use migration::MigrationRegistry;
+pub use scene_resources::SaveWithScene;
+pub use scene_diff::SceneDiff;
+pub use scene_watcher::SceneWatcher;
 pub use serialization::SceneSerializer;
\ No newline at end of file
@@ -0,0 +1,418 @@
+use std::any::type_name;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use bevy_ecs::{
+    entity::{Entity, EntityMap},
+    reflect::ReflectComponent,
+    world::World,
+};
+use bevy_hierarchy::{Children, DespawnRecursiveExt, Parent};
+use bevy_reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy_reflect::{GetPath, TypeRegistryArc};
+use serde::de::DeserializeSeed;
+
+use crate::core::errors::EngineError;
+use crate::core::plugins::components::{
+    PrefabInstanceComponent,
+    PrefabMemberComponent,
+    PrefabOverride,
+    TransformComponent,
+};
+use crate::core::project::CurrentProject;
+use crate::core::scene::entity_remap::map_entity_references;
+use crate::core::scene::scene_format::parse_scene;
+use crate::core::scene::{
+    DynamicScene,
+    DynamicSceneBuilder,
+    MigrationRegistry,
+    TypeRegistryResource,
+};
+
+// where prefabs live in a project
+pub const PREFAB_DIR: &str = "prefabs";
+
+// the transform fields every instance overrides so it stays where it was put
+const INSTANCE_TRANSFORM_FIELDS: [&str; 3] = ["global_position", "rotation", "scale"];
+
+fn prefab_error(prefab: &str, reason: impl ToString) -> EngineError {
+    EngineError::Prefab{
+        path: String::from(prefab),
+        reason: reason.to_string(),
+    }
+}
+
+// prefabs are stored on instances relative to the project so scenes can be moved around
+pub fn resolve_prefab_path(world: &World, prefab: &str) -> String {
+    match world.get_resource::<CurrentProject>() {
+        Some(project) => project.path.join(prefab).to_string_lossy().to_string(),
+        None => String::from(prefab),
+    }
+}
+
+// the prefab path for a prefab name, ie "tree" is "prefabs/tree.ron"
+pub fn prefab_path(name: &str) -> String {
+    let name = crate::core::project::scene_file_name(name);
+    format!("{}/{}", PREFAB_DIR, name)
+}
+
+// an entity and everything under it
+fn subtree(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().copied());
+        }
+    }
+    entities
+}
+
+// saves root and its children as a prefab, then brings every instance of it up to date
+pub fn save_prefab(world: &mut World, root: Entity, prefab: &str) -> Result<(), EngineError> {
+    if world.get_entity(root).is_none() {
+        return Err(prefab_error(prefab, "the entity doesn't exist"));
+    }
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+    let entities = subtree(world, root);
+    let mut builder = DynamicSceneBuilder::from_world_with_type_registry(world, registry.clone());
+    builder.extract_entities(entities.iter().copied());
+    let mut scene = builder.build();
+
+    // saving over the prefab an instance came from keeps the ids the prefab already uses, so
+    // every other instance lines up with the new version
+    let ids = prefab_ids(world, root, prefab, &entities);
+
+    // links to other prefabs and where the root sits in the world aren't part of the prefab
+    for entity in scene.entities.iter_mut() {
+        let is_root = entity.entity == root.index();
+        entity.components.retain(|c| {
+            let name = c.type_name();
+            name != type_name::<PrefabInstanceComponent>()
+                && name != type_name::<PrefabMemberComponent>()
+                && !(is_root && name == type_name::<Parent>())
+        });
+    }
+
+    for entity in scene.entities.iter_mut() {
+        let saved_id = entity.entity;
+        entity.entity = ids.get(Entity::from_raw(saved_id)).unwrap().index();
+        entity.components.retain_mut(|component| {
+            if map_entity_references(component.as_mut(), &ids) {
+                return true;
+            }
+            // it would point at whatever entity has that id once the prefab is instantiated
+            log::warn!(
+                "Leaving {} on entity {} out of prefab {}. It references an entity outside the prefab.",
+                component.type_name(),
+                saved_id,
+                prefab,
+            );
+            false
+        });
+    }
+    if is_instance_of(world, root, prefab) {
+        // entities added to the instance are now part of the prefab
+        for entity in entities.iter() {
+            if world.get::<PrefabMemberComponent>(*entity).is_none() {
+                let source = ids.get(Entity::from_raw(entity.index())).unwrap().index();
+                world.entity_mut(*entity).insert(PrefabMemberComponent{
+                    instance: root,
+                    source,
+                });
+            }
+        }
+    }
+
+    let path = resolve_prefab_path(world, prefab);
+    if let Some(dir) = Path::new(&path).parent() {
+        fs::create_dir_all(dir).map_err(|e| prefab_error(prefab, e))?;
+    }
+    log::info!("Saving prefab {}...", path);
+    scene.write_to_file(&path, &registry)?;
+    refresh_prefab_instances(world, prefab)?;
+    Ok(())
+}
+
+fn is_instance_of(world: &World, root: Entity, prefab: &str) -> bool {
+    world
+        .get::<PrefabInstanceComponent>(root)
+        .map_or(false, |instance| instance.prefab == prefab)
+}
+
+// the id every entity of the subtree gets in the prefab file. members of an instance of the
+// same prefab keep their source id, everything else gets an id the prefab doesn't use yet
+fn prefab_ids(world: &World, root: Entity, prefab: &str, entities: &[Entity]) -> EntityMap {
+    let mut ids = EntityMap::default();
+    if !is_instance_of(world, root, prefab) {
+        for entity in entities.iter() {
+            ids.insert(Entity::from_raw(entity.index()), Entity::from_raw(entity.index()));
+        }
+        return ids;
+    }
+
+    let source = |entity: &Entity| world
+        .get::<PrefabMemberComponent>(*entity)
+        .filter(|member| member.instance == root)
+        .map(|member| member.source);
+    let mut next_id = entities.iter().filter_map(source).max().map_or(0, |id| id + 1);
+    for entity in entities.iter() {
+        let id = source(entity).unwrap_or_else(|| {
+            next_id += 1;
+            next_id - 1
+        });
+        ids.insert(Entity::from_raw(entity.index()), Entity::from_raw(id));
+    }
+    ids
+}
+
+fn read_prefab(world: &World, prefab: &str) -> Result<DynamicScene, EngineError> {
+    let path = resolve_prefab_path(world, prefab);
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+    let migrations = world.get_resource::<MigrationRegistry>().cloned().unwrap_or_default();
+    let bytes = fs::read(&path).map_err(|e| EngineError::SceneRead{
+        path: path.clone(),
+        reason: e.to_string(),
+    })?;
+    parse_scene(&path, &bytes, &registry, &migrations)
+}
+
+// the prefab entity without a parent
+fn prefab_root(scene: &DynamicScene) -> Option<u32> {
+    let parent = type_name::<Parent>();
+    scene.entities
+        .iter()
+        .find(|e| !e.components.iter().any(|c| c.type_name() == parent))
+        .map(|e| e.entity)
+}
+
+// spawns a prefab with its root at transform. returns the root
+pub fn instantiate_prefab(world: &mut World, prefab: &str, transform: TransformComponent) -> Result<Entity, EngineError> {
+    let scene = read_prefab(world, prefab)?;
+    let root_source = prefab_root(&scene).ok_or_else(|| prefab_error(prefab, "it has no root entity"))?;
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+
+    let mut entity_map = EntityMap::default();
+    scene
+        .write_to_world_with(world, &mut entity_map, &registry)
+        .map_err(|e| prefab_error(prefab, e))?;
+    let root = entity_map.get(Entity::from_raw(root_source)).unwrap();
+    for scene_entity in scene.entities.iter() {
+        let entity = entity_map.get(Entity::from_raw(scene_entity.entity)).unwrap();
+        world.entity_mut(entity).insert(PrefabMemberComponent{
+            instance: root,
+            source: scene_entity.entity,
+        });
+    }
+    world.entity_mut(root).insert(PrefabInstanceComponent{
+        prefab: String::from(prefab),
+        overrides: Vec::new(),
+    });
+
+    world.entity_mut(root).insert(transform);
+    for field in INSTANCE_TRANSFORM_FIELDS {
+        record_override(world, root, type_name::<TransformComponent>(), field)?;
+    }
+    log::info!("Spawned prefab {} as {:?}", prefab, root);
+    Ok(root)
+}
+
+// the entities of an instance by their id in the prefab
+fn instance_members(world: &mut World, root: Entity) -> HashMap<u32, Entity> {
+    world
+        .query::<(Entity, &PrefabMemberComponent)>()
+        .iter(world)
+        .filter(|(_, member)| member.instance == root)
+        .map(|(entity, member)| (member.source, entity))
+        .collect()
+}
+
+// rewrites every instance of a prefab from the prefab file, keeping their overrides. returns
+// how many instances there were
+pub fn refresh_prefab_instances(world: &mut World, prefab: &str) -> Result<usize, EngineError> {
+    let instances: Vec<Entity> = world
+        .query::<(Entity, &PrefabInstanceComponent)>()
+        .iter(world)
+        .filter(|(_, instance)| instance.prefab == prefab)
+        .map(|(entity, _)| entity)
+        .collect();
+    if instances.is_empty() {
+        return Ok(0);
+    }
+
+    let scene = read_prefab(world, prefab)?;
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+    for root in instances.iter() {
+        refresh_instance(world, *root, prefab, &scene, &registry)?;
+    }
+    Ok(instances.len())
+}
+
+// refreshes the instances of every prefab in the world. a prefab that can't be read leaves its
+// instances as they were saved
+pub fn refresh_all_prefab_instances(world: &mut World){
+    let prefabs: HashSet<String> = world
+        .query::<&PrefabInstanceComponent>()
+        .iter(world)
+        .map(|instance| instance.prefab.clone())
+        .collect();
+    for prefab in prefabs {
+        if let Err(e) = refresh_prefab_instances(world, &prefab) {
+            log::warn!("Couldn't update instances of {}: {}", prefab, e);
+        }
+    }
+}
+
+fn refresh_instance(world: &mut World, root: Entity, prefab: &str, scene: &DynamicScene, registry: &TypeRegistryArc) -> Result<(), EngineError> {
+    let members = instance_members(world, root);
+
+    // entities taken out of the prefab go from every instance
+    for (source, entity) in members.iter() {
+        let in_prefab = scene.entities.iter().any(|e| e.entity == *source);
+        if !in_prefab && *entity != root && world.get_entity(*entity).is_some() {
+            world.entity_mut(*entity).despawn_recursive();
+        }
+    }
+
+    let mut entity_map = EntityMap::default();
+    for (source, entity) in members.iter() {
+        entity_map.insert(Entity::from_raw(*source), *entity);
+    }
+    scene
+        .write_to_world_with(world, &mut entity_map, registry)
+        .map_err(|e| prefab_error(prefab, e))?;
+
+    // entities added to the prefab need their link
+    for scene_entity in scene.entities.iter() {
+        let entity = entity_map.get(Entity::from_raw(scene_entity.entity)).unwrap();
+        world.entity_mut(entity).insert(PrefabMemberComponent{
+            instance: root,
+            source: scene_entity.entity,
+        });
+    }
+
+    remove_dropped_components(world, root, scene, &entity_map, registry);
+    apply_overrides(world, root, registry);
+    Ok(())
+}
+
+// writing the prefab only adds and overwrites components, so ones taken out of the prefab are
+// removed here. components the instance overrides stay
+fn remove_dropped_components(world: &mut World, root: Entity, scene: &DynamicScene, entity_map: &EntityMap, registry: &TypeRegistryArc){
+    let overridden: HashSet<(u32, String)> = match world.get::<PrefabInstanceComponent>(root) {
+        Some(instance) => instance.overrides
+            .iter()
+            .map(|o| (o.entity, o.component.clone()))
+            .collect(),
+        None => HashSet::new(),
+    };
+    // the links and the hierarchy are kept up to date above
+    let not_in_prefabs = [
+        type_name::<PrefabInstanceComponent>(),
+        type_name::<PrefabMemberComponent>(),
+        type_name::<Parent>(),
+        type_name::<Children>(),
+    ];
+    let registry = registry.read();
+    for scene_entity in scene.entities.iter() {
+        let entity = entity_map.get(Entity::from_raw(scene_entity.entity)).unwrap();
+        let in_prefab: HashSet<&str> = scene_entity.components.iter().map(|c| c.type_name()).collect();
+        for registration in registry.iter() {
+            let name = registration.type_name();
+            let reflect_component = match registration.data::<ReflectComponent>() {
+                Some(r) => r,
+                None => continue,
+            };
+            if not_in_prefabs.contains(&name)
+                || in_prefab.contains(name)
+                || overridden.contains(&(scene_entity.entity, String::from(name)))
+                || reflect_component.reflect(world, entity).is_none() {
+                continue;
+            }
+            reflect_component.remove(world, entity);
+        }
+    }
+}
+
+fn apply_overrides(world: &mut World, root: Entity, registry: &TypeRegistryArc){
+    let overrides = match world.get::<PrefabInstanceComponent>(root) {
+        Some(instance) => instance.overrides.clone(),
+        None => return,
+    };
+    let members = instance_members(world, root);
+    for prefab_override in overrides.iter() {
+        let entity = match members.get(&prefab_override.entity) {
+            Some(e) => *e,
+            None => {
+                log::warn!(
+                    "Dropping override of {}.{}. Entity {} isn't in the prefab anymore.",
+                    prefab_override.component,
+                    prefab_override.field,
+                    prefab_override.entity,
+                );
+                continue;
+            }
+        };
+        if let Err(e) = apply_override(world, entity, prefab_override, registry) {
+            log::warn!("Couldn't override {}.{}: {}", prefab_override.component, prefab_override.field, e);
+        }
+    }
+}
+
+fn apply_override(world: &mut World, entity: Entity, prefab_override: &PrefabOverride, registry: &TypeRegistryArc) -> Result<(), String> {
+    let registry = registry.read();
+    let reflect_component = registry
+        .get_with_name(&prefab_override.component)
+        .and_then(|registration| registration.data::<ReflectComponent>())
+        .ok_or("it isn't a registered component")?;
+    let mut component = reflect_component
+        .reflect_mut(world, entity)
+        .ok_or("the entity doesn't have it")?;
+    let field = (*component).path_mut(&prefab_override.field).map_err(|e| e.to_string())?;
+    let field_registration = registry
+        .get_with_name(field.type_name())
+        .ok_or("the field's type isn't registered")?;
+
+    let mut deserializer = ron::de::Deserializer::from_str(&prefab_override.value).map_err(|e| e.to_string())?;
+    let value = TypedReflectDeserializer::new(field_registration, &registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())?;
+    field.apply(&*value);
+    Ok(())
+}
+
+// makes the current value of a field on a prefab instance's entity an override, so it's kept
+// when the prefab changes
+pub fn record_override(world: &mut World, entity: Entity, component: &str, field: &str) -> Result<(), EngineError> {
+    let member = world
+        .get::<PrefabMemberComponent>(entity)
+        .cloned()
+        .ok_or_else(|| prefab_error(component, "the entity isn't part of a prefab instance"))?;
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+    let value = {
+        let registry = registry.read();
+        let reflect_component = registry
+            .get_with_name(component)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .ok_or_else(|| prefab_error(component, "it isn't a registered component"))?;
+        let reflected = reflect_component
+            .reflect(world, entity)
+            .ok_or_else(|| prefab_error(component, "the entity doesn't have it"))?;
+        let field_value = reflected.path(field).map_err(|e| prefab_error(component, e))?;
+        ron::to_string(&TypedReflectSerializer::new(field_value, &registry))
+            .map_err(|e| prefab_error(component, e))?
+    };
+
+    let mut instance = world
+        .get_mut::<PrefabInstanceComponent>(member.instance)
+        .ok_or_else(|| prefab_error(component, "the instance root is gone"))?;
+    instance.set_override(PrefabOverride{
+        entity: member.source,
+        component: String::from(component),
+        field: String::from(field),
+        value,
+    });
+    Ok(())
+}
@@ -3,22 +3,36 @@ use bevy_ecs::{
     prelude::SystemStage,
     world::World,
     schedule::Stage,
+    schedule::IntoSystemDescriptor,
     system::Resource,
 };
 
 
 use bevy_reflect::TypeRegistryArc;
+use crate::core::scene::type_registration::register_engine_types;
 use bevy_ecs::prelude::Events;
-use crate::core::events::project_events::SaveEvent;
+use crate::core::events::project_events::{SaveEvent, SaveAsEvent};
+use crate::core::events::prefab_events::{SavePrefabEvent, InstantiatePrefabEvent};
 use crate::core::managers::SceneManagerMessagePump;
 use crate::core::events::project_events::CreateProjectEvent;
 use crate::core::events::project_events::CloseProjectEvent;
 use crate::core::events::project_events::OpenProjectEvent;
 use crate::core::events::menu_messages::MenuMessage;
 use crate::core::events::terrain_events::TerrainRecalculateEvent;
-use crate::core::systems::initalize_editor_interface;
 use crate::core::systems::ui_systems::EntityInspectionUiSystem;
+use crate::core::systems::ui_systems::ComponentLibraryUiSystem;
 use crate::core::systems::ui_systems::PanelInitSystem;
+use crate::core::systems::ui_systems::LoadingScreenUiSystem;
+use crate::core::systems::ui_systems::ProjectPickerUiSystem;
+use crate::core::systems::ui_systems::PlayControlsUiSystem;
+use crate::core::systems::ui_systems::{ErrorUiSystem, ErrorLog};
+use crate::core::events::error_events::ErrorEvent;
+use crate::core::events::application_state_events::ApplicationStateEvent;
+use crate::core::events::play_mode_events::PlayModeEvent;
+use crate::core::scene::schedule_registry::{ScheduleRegistry, SceneSchedule};
+use crate::core::scene::migration::MigrationRegistry;
+use crate::core::events::scene_events::{SceneLoadedEvent, SceneUnloadedEvent, SceneLoadProgressEvent};
+use crate::core::plugins::components::ui::main_menu_component::EditorUiState;
 
 use std::{
     cell::{
@@ -56,15 +70,28 @@ use crate::core::systems::{
     SceneSerializationSystem,
     TerrainUpdateSystem,
     ShowNewProjectWindow,
+    ShowSaveAsWindow,
     ShowOpenProjectWindow,
     ProjectCreationSystem,
     OpenProjectSystem,
+    PrefabSystem,
+    rendering_enabled,
+    VelocitySystem,
 };
 
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct TypeRegistryResource(pub TypeRegistryArc);
 
+// a registry with every engine type in it
+impl Default for TypeRegistryResource {
+    fn default() -> Self {
+        let registry = TypeRegistryArc::default();
+        register_engine_types(&mut registry.write());
+        TypeRegistryResource(registry)
+    }
+}
+
 
 pub struct Scene<S>{
     pub world: Option<RefCell<World>>,
@@ -83,6 +110,22 @@ pub struct Staged{
     pub teardown_schedule: Option<Schedule>,
 }
 
+// initializes geometry, terrain, cameras and renderables. run when a scene is activated and
+// again whenever entities are respawned on an active scene
+fn setup_schedule() -> Schedule {
+    let mut schedule = Schedule::default();
+    schedule
+    .add_stage("geometry_init", SystemStage::parallel()
+        .with_run_criteria(rendering_enabled)
+        .with_system(GeometryInitializerSystem)
+        .with_system(TerrainInitSystem)
+    ).add_stage("final_init", SystemStage::parallel()
+        .with_system(CameraInitSystem)
+        .with_system(RenderableInitializerSystem.with_run_criteria(rendering_enabled))
+    );
+    schedule
+}
+
 impl Scene<Inactive> {
     pub fn new() -> Self {
         Scene{
@@ -113,6 +156,18 @@ impl Scene<Staged> {
             .unwrap()
             .init_resource::<Events<SaveEvent>>();
 
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<SaveAsEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<SavePrefabEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<InstantiatePrefabEvent>>();
+
         scene.get_world()
             .unwrap()
             .init_resource::<TypeRegistryResource>();
@@ -141,41 +196,47 @@ impl Scene<Staged> {
             .unwrap()
             .init_resource::<Events<TerrainRecalculateEvent>>();
 
-        {
-            let mut world = scene.get_world().unwrap();
-            let registry_arc = world.get_resource_mut::<TypeRegistryResource>().unwrap();
-            let mut registry = registry_arc.0.write();
-            registry.register::<AppInterfaceFlag>();
-            registry.register::<DebugUiComponent>();
-            registry.register::<FileSubMenuComponent>();
-            registry.register::<TerrainComponent>();
-            registry.register::<TransformComponent>();
-            registry.register::<TerrainUiComponent>();
-            registry.register::<RenderableComponent>();
-            registry.register::<GeometryType>();
-            registry.register::<GeometryComponent>();
-            registry.register::<DirectionalLightComponent>();
-            registry.register::<AmbientLightingComponent>();
-            registry.register::<CameraComponent>();
-            registry.register::<InputComponent>();
-        }
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<ApplicationStateEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<EditorUiState>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<PlayModeEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<SceneLoadedEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<SceneUnloadedEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<SceneLoadProgressEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<Events<ErrorEvent>>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<MigrationRegistry>();
+
+        scene.get_world()
+            .unwrap()
+            .init_resource::<ErrorLog>();
+
     }
 
     fn create_setup_schedule(&mut self){
-        let mut schedule = Schedule::default();
         log::info!("Creating setup schedule.");
-        schedule
-        .add_stage("ui_init", SystemStage::parallel()
-            .with_system(initalize_editor_interface)
-        )
-        .add_stage("geometry_init", SystemStage::parallel()
-            .with_system(GeometryInitializerSystem)
-            .with_system(TerrainInitSystem)
-        ).add_stage("final_init", SystemStage::parallel()
-            .with_system(CameraInitSystem)
-            .with_system(RenderableInitializerSystem)
-        );
-        self.state.setup_schedule = Some(schedule);
+        self.state.setup_schedule = Some(setup_schedule());
     }
     
     fn create_teardown_schedule(&mut self){
@@ -273,39 +334,61 @@ impl Scene<Active> {
         .add_stage("camera_move", SystemStage::parallel()
             .with_system(CameraMoveSystem)
         ).add_stage_after("camera_move", "camera_update", SystemStage::parallel()
+            .with_run_criteria(rendering_enabled)
             .with_system(CameraUpdateSystem)
         ).add_stage("wireframe_input_system", SystemStage::parallel()
+            .with_run_criteria(rendering_enabled)
             .with_system(RenderableAssemblyStateModifierSystem)
         ).add_stage("assembly_state_modifier_system", SystemStage::parallel()
+            .with_run_criteria(rendering_enabled)
             .with_system(TerrainAssemblyStateModifierSystem)
         ).add_stage_after("camera_update", "main", SystemStage::single_threaded()
+            .with_run_criteria(rendering_enabled)
             .with_system(RenderableDrawSystem)
             .with_system(DirectionalLightingSystem)
             .with_system(AmbientLightingSystem)
             .with_system(TerrainDrawSystem)
         ).add_stage("pre_ui", SystemStage::single_threaded()
+            .with_run_criteria(rendering_enabled)
             .with_system(PanelInitSystem)
         )
         .add_stage_after("pre_ui", "ui", SystemStage::single_threaded()
+            .with_run_criteria(rendering_enabled)
             .with_system(TerrainUiSystem)
             .with_system(CameraUiSystem)
             .with_system(FileSubMenuSystem)
             .with_system(ShowNewProjectWindow)
+            .with_system(ShowSaveAsWindow)
             .with_system(ShowOpenProjectWindow)
             .with_system(TransformUiSystem)
             .with_system(SceneGraphUiSystem)
             .with_system(EntityInspectionUiSystem)
+            .with_system(ComponentLibraryUiSystem)
+            .with_system(LoadingScreenUiSystem)
+            .with_system(ProjectPickerUiSystem)
+            .with_system(PlayControlsUiSystem)
+            .with_system(ErrorUiSystem)
         ).add_stage_after("ui", "event_processing", SystemStage::parallel()
             .with_system(SceneSerializationSystem)
-            .with_system(TerrainUpdateSystem)
+            .with_system(TerrainUpdateSystem.with_run_criteria(rendering_enabled))
             .with_system(ProjectCreationSystem)
             .with_system(OpenProjectSystem)
+            .with_system(PrefabSystem)
         );
         self.state.render_schedule = Some(schedule);
     }
 
+    // runs at the application's fixed tick rate. gameplay and physics go here so they
+    // don't depend on the frame rate
     pub fn create_update_schedule(&mut self){
-        let schedule = Schedule::default();
+        let mut schedule = Schedule::default();
+
+        schedule
+        .add_stage("pre_update", SystemStage::parallel())
+        .add_stage_after("pre_update", "physics", SystemStage::parallel()
+            .with_system(VelocitySystem)
+        ).add_stage_after("physics", "update", SystemStage::parallel())
+        .add_stage_after("update", "post_update", SystemStage::parallel());
         self.state.update_schedule = Some(schedule);
     }
 
@@ -314,6 +397,27 @@ impl Scene<Active> {
         self.state.teardown_schedule = Some(schedule);
     }
 
+    // reruns the setup systems on an active scene, ie after a snapshot is restored
+    pub fn run_setup_schedule(&mut self){
+        log::info!("Running setup schedule for active scene...");
+        let mut schedule = setup_schedule();
+        let registry = self.get_world().unwrap().get_resource::<ScheduleRegistry>().cloned();
+        if let Some(registry) = registry {
+            registry.apply(SceneSchedule::Setup, &mut schedule);
+        }
+        schedule.run(&mut *self.get_world().unwrap());
+    }
+
+    // the active scene's schedules. the setup schedule is rebuilt every time it runs so it isn't here
+    pub fn schedule_mut(&mut self, schedule: SceneSchedule) -> Option<&mut Schedule> {
+        match schedule.schedule() {
+            SceneSchedule::Update => self.state.update_schedule.as_mut(),
+            SceneSchedule::Render => self.state.render_schedule.as_mut(),
+            SceneSchedule::Teardown => self.state.teardown_schedule.as_mut(),
+            _ => None,
+        }
+    }
+
     pub fn run_render_schedule(&mut self){
         let mut schedule = self.state.render_schedule.take().unwrap();
         schedule.run(&mut *self.get_world().unwrap());
@@ -344,6 +448,11 @@ impl <Active> Scene<Active>{
 
 impl From<Scene<Staged>> for Scene<Active> {
     fn from(mut staged_scene: Scene<Staged>) -> Scene<Active> {
+        // anything registered from outside the engine goes in before the schedules first run
+        let registry = staged_scene.get_world().unwrap().get_resource::<ScheduleRegistry>().cloned();
+        if let (Some(registry), Some(setup_schedule)) = (registry.as_ref(), staged_scene.state.setup_schedule.as_mut()) {
+            registry.apply(SceneSchedule::Setup, setup_schedule);
+        }
         staged_scene.run_setup_schedule();
         let mut scene = Scene{
             world: staged_scene.world,
@@ -357,6 +466,11 @@ impl From<Scene<Staged>> for Scene<Active> {
         scene.create_render_schedule();
         scene.create_update_schedule();
         scene.create_teardown_schedule();
+        if let Some(registry) = registry {
+            for schedule in [SceneSchedule::Update, SceneSchedule::Render, SceneSchedule::Teardown] {
+                registry.apply(schedule, scene.schedule_mut(schedule).unwrap());
+            }
+        }
         scene
     }
 }
@@ -0,0 +1,465 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use bevy_ecs::{
+    entity::{Entity, EntityMap},
+    reflect::{ReflectComponent, ReflectResource},
+    world::World,
+};
+use bevy_reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy_reflect::{GetPath, Reflect, ReflectRef, TypeRegistry, TypeRegistryArc};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+
+use crate::core::errors::EngineError;
+use crate::core::scene::dynamic_scene::serialize_ron;
+use crate::core::scene::entity_remap::map_entity_references;
+use crate::core::scene::DynamicScene;
+
+// one difference between two scenes. entities are their ids in the scenes that were compared,
+// values are ron so a diff can be read and reviewed without the engine. every change keeps the
+// value it replaced so a diff can be inverted for undo
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SceneChange{
+    AddEntity{
+        entity: u32,
+    },
+    // the entity's components are removed by changes before this one
+    RemoveEntity{
+        entity: u32,
+    },
+    AddComponent{
+        entity: u32,
+        component: String,
+        value: String,
+    },
+    RemoveComponent{
+        entity: u32,
+        component: String,
+        value: String,
+    },
+    // field is a reflect path into the component, ie "global_position.x". empty for components
+    // that are changed as a whole, like tuple structs and values
+    ChangeField{
+        entity: u32,
+        component: String,
+        field: String,
+        old: String,
+        new: String,
+    },
+    AddResource{
+        resource: String,
+        value: String,
+    },
+    RemoveResource{
+        resource: String,
+        value: String,
+    },
+    ChangeResourceField{
+        resource: String,
+        field: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl SceneChange{
+    fn inverse(&self) -> SceneChange {
+        match self.clone() {
+            SceneChange::AddEntity{entity} => SceneChange::RemoveEntity{entity},
+            SceneChange::RemoveEntity{entity} => SceneChange::AddEntity{entity},
+            SceneChange::AddComponent{entity, component, value} => SceneChange::RemoveComponent{entity, component, value},
+            SceneChange::RemoveComponent{entity, component, value} => SceneChange::AddComponent{entity, component, value},
+            SceneChange::ChangeField{entity, component, field, old, new} => SceneChange::ChangeField{
+                entity,
+                component,
+                field,
+                old: new,
+                new: old,
+            },
+            SceneChange::AddResource{resource, value} => SceneChange::RemoveResource{resource, value},
+            SceneChange::RemoveResource{resource, value} => SceneChange::AddResource{resource, value},
+            SceneChange::ChangeResourceField{resource, field, old, new} => SceneChange::ChangeResourceField{
+                resource,
+                field,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+// what changed between two scenes, in the order it has to be applied. new entities come first
+// so references to them can be mapped, then components and resources, removed entities last
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SceneDiff{
+    pub changes: Vec<SceneChange>,
+}
+
+fn patch_error(reason: impl ToString) -> EngineError {
+    EngineError::ScenePatch(reason.to_string())
+}
+
+impl SceneDiff{
+    // every change that turns old into new
+    pub fn between(old: &DynamicScene, new: &DynamicScene, registry: &TypeRegistryArc) -> Result<SceneDiff, EngineError> {
+        let registry = registry.read();
+        let components = |scene: &DynamicScene| -> BTreeMap<u32, BTreeMap<String, Box<dyn Reflect>>> {
+            scene.entities
+                .iter()
+                .map(|e| (e.entity, e.components.iter().map(|c| (String::from(c.type_name()), c.clone_value())).collect()))
+                .collect()
+        };
+        let old_entities = components(old);
+        let new_entities = components(new);
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+        let ids: BTreeSet<u32> = old_entities.keys().chain(new_entities.keys()).copied().collect();
+        let no_components = BTreeMap::new();
+        for id in ids {
+            let (old_components, new_components) = match (old_entities.get(&id), new_entities.get(&id)) {
+                (None, Some(n)) => {
+                    added.push(SceneChange::AddEntity{ entity: id });
+                    (&no_components, n)
+                },
+                (Some(o), None) => {
+                    removed.push(SceneChange::RemoveEntity{ entity: id });
+                    (o, &no_components)
+                },
+                (Some(o), Some(n)) => (o, n),
+                (None, None) => unreachable!(),
+            };
+
+            let names: BTreeSet<&String> = old_components.keys().chain(new_components.keys()).collect();
+            for name in names {
+                match (old_components.get(name), new_components.get(name)) {
+                    (None, Some(n)) => changed.push(SceneChange::AddComponent{
+                        entity: id,
+                        component: name.clone(),
+                        value: to_ron(&**n, &registry)?,
+                    }),
+                    (Some(o), None) => changed.push(SceneChange::RemoveComponent{
+                        entity: id,
+                        component: name.clone(),
+                        value: to_ron(&**o, &registry)?,
+                    }),
+                    (Some(o), Some(n)) => {
+                        let mut fields = Vec::new();
+                        diff_fields("", &**o, &**n, &registry, &mut fields)?;
+                        changed.extend(fields.into_iter().map(|(field, old, new)| SceneChange::ChangeField{
+                            entity: id,
+                            component: name.clone(),
+                            field,
+                            old,
+                            new,
+                        }));
+                    },
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+
+        let resources = |scene: &DynamicScene| -> BTreeMap<String, Box<dyn Reflect>> {
+            scene.resources.iter().map(|r| (String::from(r.type_name()), r.clone_value())).collect()
+        };
+        let old_resources = resources(old);
+        let new_resources = resources(new);
+        let names: BTreeSet<&String> = old_resources.keys().chain(new_resources.keys()).collect();
+        for name in names {
+            match (old_resources.get(name), new_resources.get(name)) {
+                (None, Some(n)) => changed.push(SceneChange::AddResource{
+                    resource: name.clone(),
+                    value: to_ron(&**n, &registry)?,
+                }),
+                (Some(o), None) => changed.push(SceneChange::RemoveResource{
+                    resource: name.clone(),
+                    value: to_ron(&**o, &registry)?,
+                }),
+                (Some(o), Some(n)) => {
+                    let mut fields = Vec::new();
+                    diff_fields("", &**o, &**n, &registry, &mut fields)?;
+                    changed.extend(fields.into_iter().map(|(field, old, new)| SceneChange::ChangeResourceField{
+                        resource: name.clone(),
+                        field,
+                        old,
+                        new,
+                    }));
+                },
+                (None, None) => unreachable!(),
+            }
+        }
+
+        added.append(&mut changed);
+        added.append(&mut removed);
+        Ok(SceneDiff{ changes: added })
+    }
+
+    // every change that turns scene into what's in world now. entities are compared by index, so
+    // scene should have been taken from world or loaded into an empty one
+    pub fn against_world(scene: &DynamicScene, world: &World, registry: &TypeRegistryArc) -> Result<SceneDiff, EngineError> {
+        SceneDiff::between(scene, &DynamicScene::from_world(world, registry), registry)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // a diff that undoes this one
+    pub fn inverse(&self) -> SceneDiff {
+        SceneDiff{
+            changes: self.changes.iter().rev().map(SceneChange::inverse).collect(),
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, EngineError> {
+        serialize_ron(self).map_err(patch_error)
+    }
+
+    pub fn from_ron(ron_str: &str) -> Result<SceneDiff, EngineError> {
+        ron::from_str(ron_str).map_err(patch_error)
+    }
+
+    // applies the changes to world. entity_map maps the diff's entity ids to entities in world
+    // and gets the entities the diff adds. use world_entity_map for a diff taken from world itself.
+    // a diff that can't be applied in full isn't applied at all
+    pub fn apply(&self, world: &mut World, entity_map: &mut EntityMap, registry: &TypeRegistryArc) -> Result<(), EngineError> {
+        let registry = registry.read();
+        self.check(world, entity_map, &registry)?;
+        for change in self.changes.iter() {
+            match change {
+                SceneChange::AddEntity{entity} => {
+                    let spawned = world.spawn_empty().id();
+                    entity_map.insert(Entity::from_raw(*entity), spawned);
+                },
+                SceneChange::RemoveEntity{entity} => {
+                    let target = mapped(entity_map, *entity)?;
+                    world.despawn(target);
+                },
+                SceneChange::AddComponent{entity, component, value} => {
+                    let target = mapped(entity_map, *entity)?;
+                    let reflect_component = reflect_component(&registry, component)?;
+                    let value = from_ron(component, value, entity_map, &registry)?;
+                    reflect_component.apply_or_insert(world, target, &*value);
+                },
+                SceneChange::RemoveComponent{entity, component, ..} => {
+                    let target = mapped(entity_map, *entity)?;
+                    reflect_component(&registry, component)?.remove(world, target);
+                },
+                SceneChange::ChangeField{entity, component, field, new, ..} => {
+                    let target = mapped(entity_map, *entity)?;
+                    let reflect_component = reflect_component(&registry, component)?;
+                    let mut current = reflect_component
+                        .reflect_mut(world, target)
+                        .ok_or_else(|| patch_error(format!("entity {} doesn't have {}", entity, component)))?;
+                    apply_field(&mut *current, field, new, entity_map, &registry)?;
+                },
+                SceneChange::AddResource{resource, value} => {
+                    let reflect_resource = reflect_resource(&registry, resource)?;
+                    let value = from_ron(resource, value, entity_map, &registry)?;
+                    reflect_resource.apply_or_insert(world, &*value);
+                },
+                SceneChange::RemoveResource{resource, ..} => {
+                    reflect_resource(&registry, resource)?.remove(world);
+                },
+                SceneChange::ChangeResourceField{resource, field, new, ..} => {
+                    let reflect_resource = reflect_resource(&registry, resource)?;
+                    let mut current = reflect_resource
+                        .reflect_mut(world)
+                        .ok_or_else(|| patch_error(format!("{} isn't in the world", resource)))?;
+                    apply_field(&mut *current, field, new, entity_map, &registry)?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    // runs the changes against copies of what they touch, so a change that can't be applied is
+    // found before world is changed
+    fn check(&self, world: &World, entity_map: &EntityMap, registry: &TypeRegistry) -> Result<(), EngineError> {
+        let mut entity_map = copy_entity_map(entity_map);
+        // entities the diff adds don't exist yet. they're stood in for by ids from the top of the
+        // range, which references to them are mapped to
+        let mut added = HashSet::new();
+        let mut removed = HashSet::new();
+        let exists = |target: Entity, added: &HashSet<Entity>, removed: &HashSet<Entity>| {
+            !removed.contains(&target) && (added.contains(&target) || world.get_entity(target).is_some())
+        };
+        // components and resources as the changes so far left them. None is removed
+        let mut components: HashMap<(Entity, String), Option<Box<dyn Reflect>>> = HashMap::new();
+        let mut resources: HashMap<String, Option<Box<dyn Reflect>>> = HashMap::new();
+
+        for change in self.changes.iter() {
+            match change {
+                SceneChange::AddEntity{entity} => {
+                    let placeholder = Entity::from_raw(u32::MAX - added.len() as u32);
+                    added.insert(placeholder);
+                    entity_map.insert(Entity::from_raw(*entity), placeholder);
+                },
+                SceneChange::RemoveEntity{entity} => {
+                    let target = mapped(&entity_map, *entity)?;
+                    if !exists(target, &added, &removed) {
+                        return Err(patch_error(format!("entity {} doesn't exist", entity)));
+                    }
+                    removed.insert(target);
+                },
+                SceneChange::AddComponent{entity, component, value} => {
+                    let target = existing(&entity_map, *entity, |t| exists(t, &added, &removed))?;
+                    reflect_component(registry, component)?;
+                    let value = from_ron(component, value, &entity_map, registry)?;
+                    components.insert((target, component.clone()), Some(value));
+                },
+                SceneChange::RemoveComponent{entity, component, ..} => {
+                    let target = existing(&entity_map, *entity, |t| exists(t, &added, &removed))?;
+                    reflect_component(registry, component)?;
+                    components.insert((target, component.clone()), None);
+                },
+                SceneChange::ChangeField{entity, component, field, new, ..} => {
+                    let target = existing(&entity_map, *entity, |t| exists(t, &added, &removed))?;
+                    let reflect_component = reflect_component(registry, component)?;
+                    let key = (target, component.clone());
+                    let current = match components.remove(&key) {
+                        Some(current) => current,
+                        None => reflect_component.reflect(world, target).map(|c| c.clone_value()),
+                    };
+                    let mut current = current
+                        .ok_or_else(|| patch_error(format!("entity {} doesn't have {}", entity, component)))?;
+                    apply_field(&mut *current, field, new, &entity_map, registry)?;
+                    components.insert(key, Some(current));
+                },
+                SceneChange::AddResource{resource, value} => {
+                    reflect_resource(registry, resource)?;
+                    let value = from_ron(resource, value, &entity_map, registry)?;
+                    resources.insert(resource.clone(), Some(value));
+                },
+                SceneChange::RemoveResource{resource, ..} => {
+                    reflect_resource(registry, resource)?;
+                    resources.insert(resource.clone(), None);
+                },
+                SceneChange::ChangeResourceField{resource, field, new, ..} => {
+                    let reflect_resource = reflect_resource(registry, resource)?;
+                    let current = match resources.remove(resource) {
+                        Some(current) => current,
+                        None => reflect_resource.reflect(world).map(|r| r.clone_value()),
+                    };
+                    let mut current = current
+                        .ok_or_else(|| patch_error(format!("{} isn't in the world", resource)))?;
+                    apply_field(&mut *current, field, new, &entity_map, registry)?;
+                    resources.insert(resource.clone(), Some(current));
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+// maps every entity in world to itself, for applying diffs taken against world
+pub fn world_entity_map(world: &World) -> EntityMap {
+    let mut entity_map = EntityMap::default();
+    for entity in world.iter_entities() {
+        entity_map.insert(Entity::from_raw(entity.index()), entity);
+    }
+    entity_map
+}
+
+fn copy_entity_map(entity_map: &EntityMap) -> EntityMap {
+    let mut copy = EntityMap::default();
+    for from in entity_map.keys() {
+        copy.insert(from, entity_map.get(from).unwrap());
+    }
+    copy
+}
+
+fn mapped(entity_map: &EntityMap, entity: u32) -> Result<Entity, EngineError> {
+    entity_map
+        .get(Entity::from_raw(entity))
+        .map_err(|_| patch_error(format!("entity {} isn't in the entity map", entity)))
+}
+
+// the entity the diff's id maps to, as long as it's still around
+fn existing(entity_map: &EntityMap, entity: u32, exists: impl Fn(Entity) -> bool) -> Result<Entity, EngineError> {
+    let target = mapped(entity_map, entity)?;
+    if !exists(target) {
+        return Err(patch_error(format!("entity {} doesn't exist", entity)));
+    }
+    Ok(target)
+}
+
+fn reflect_component<'a>(registry: &'a TypeRegistry, component: &str) -> Result<&'a ReflectComponent, EngineError> {
+    registry
+        .get_with_name(component)
+        .and_then(|registration| registration.data::<ReflectComponent>())
+        .ok_or_else(|| patch_error(format!("{} isn't a registered component", component)))
+}
+
+fn reflect_resource<'a>(registry: &'a TypeRegistry, resource: &str) -> Result<&'a ReflectResource, EngineError> {
+    registry
+        .get_with_name(resource)
+        .and_then(|registration| registration.data::<ReflectResource>())
+        .ok_or_else(|| patch_error(format!("{} isn't a registered resource", resource)))
+}
+
+fn to_ron(value: &dyn Reflect, registry: &TypeRegistry) -> Result<String, EngineError> {
+    ron::to_string(&TypedReflectSerializer::new(value, registry)).map_err(patch_error)
+}
+
+fn from_ron(type_name: &str, value: &str, entity_map: &EntityMap, registry: &TypeRegistry) -> Result<Box<dyn Reflect>, EngineError> {
+    let registration = registry
+        .get_with_name(type_name)
+        .ok_or_else(|| patch_error(format!("{} isn't registered", type_name)))?;
+    let mut deserializer = ron::de::Deserializer::from_str(value).map_err(patch_error)?;
+    let mut value = TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(patch_error)?;
+    if !map_entity_references(&mut *value, entity_map) {
+        return Err(patch_error(format!("{} references an entity that isn't in the entity map", type_name)));
+    }
+    Ok(value)
+}
+
+// sets the field at the reflect path to the ron value. an empty path is the whole value
+fn apply_field(value: &mut dyn Reflect, field: &str, new: &str, entity_map: &EntityMap, registry: &TypeRegistry) -> Result<(), EngineError> {
+    let target_field = if field.is_empty() {
+        value
+    } else {
+        value.path_mut(field).map_err(|e| patch_error(e.to_string()))?
+    };
+    let type_name = String::from(target_field.type_name());
+    let new_value = from_ron(&type_name, new, entity_map, registry)?;
+    target_field.apply(&*new_value);
+    Ok(())
+}
+
+// structs are compared field by field so a diff says which field changed. anything else is
+// compared as a whole. changes are (field path, old ron, new ron)
+fn diff_fields(
+    path: &str,
+    old: &dyn Reflect,
+    new: &dyn Reflect,
+    registry: &TypeRegistry,
+    changes: &mut Vec<(String, String, String)>,
+) -> Result<(), EngineError> {
+    if let (ReflectRef::Struct(old_struct), ReflectRef::Struct(new_struct)) = (old.reflect_ref(), new.reflect_ref()) {
+        if old.type_name() == new.type_name() {
+            for (i, old_field) in old_struct.iter_fields().enumerate() {
+                let name = old_struct.name_at(i).unwrap();
+                if let Some(new_field) = new_struct.field(name) {
+                    let field_path = if path.is_empty() {
+                        String::from(name)
+                    } else {
+                        format!("{}.{}", path, name)
+                    };
+                    diff_fields(&field_path, old_field, new_field, registry, changes)?;
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    let old_ron = to_ron(old, registry)?;
+    let new_ron = to_ron(new, registry)?;
+    if old_ron != new_ron {
+        changes.push((String::from(path), old_ron, new_ron));
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use bevy_reflect::TypeRegistryArc;
+use bincode::Options;
+use serde::de::DeserializeSeed;
+
+use crate::core::errors::EngineError;
+use crate::core::scene::{
+    DynamicScene,
+    MigrationRegistry,
+    SceneDeserializer,
+    SceneSerializer,
+};
+
+pub const RON_SCENE_EXTENSION: &str = "ron";
+pub const BINARY_SCENE_EXTENSION: &str = "scn";
+
+// every binary scene starts with this, then the length of the encoded scene as a little
+// endian u64, then the scene itself
+const BINARY_SCENE_MAGIC: &[u8; 4] = b"EMBS";
+const BINARY_SCENE_HEADER_LEN: usize = 12;
+
+// how a scene file is encoded. ron is the source format that gets diffed and edited by hand,
+// binary is smaller and faster to load for shipping builds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneFormat{
+    Ron,
+    Binary,
+}
+
+impl SceneFormat{
+    // picked by extension. anything that isn't .scn is treated as ron
+    pub fn from_path(path: &str) -> SceneFormat {
+        match Path::new(path).extension() {
+            Some(ext) if ext == BINARY_SCENE_EXTENSION => SceneFormat::Binary,
+            _ => SceneFormat::Ron,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SceneFormat::Ron => RON_SCENE_EXTENSION,
+            SceneFormat::Binary => BINARY_SCENE_EXTENSION,
+        }
+    }
+
+    pub fn is_scene_file(path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == RON_SCENE_EXTENSION || ext == BINARY_SCENE_EXTENSION)
+    }
+}
+
+// the same options have to be used both ways, so they only live here. components in binary
+// scenes are encoded with them too
+pub(crate) fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+pub fn serialize_binary(scene: &DynamicScene, registry: &TypeRegistryArc) -> Result<Vec<u8>, bincode::Error> {
+    let payload = bincode_options().serialize(&SceneSerializer::new(scene, registry))?;
+    let mut bytes = Vec::with_capacity(BINARY_SCENE_HEADER_LEN + payload.len());
+    bytes.extend_from_slice(BINARY_SCENE_MAGIC);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+// encodes a scene in the format its path asks for
+pub fn serialize_scene(path: &str, scene: &DynamicScene, registry: &TypeRegistryArc) -> Result<Vec<u8>, EngineError> {
+    let write_error = |reason: String| EngineError::SceneWrite{
+        path: String::from(path),
+        reason,
+    };
+    match SceneFormat::from_path(path) {
+        SceneFormat::Ron => scene
+            .serialize_ron(registry)
+            .map(String::into_bytes)
+            .map_err(|e| write_error(e.to_string())),
+        SceneFormat::Binary => serialize_binary(scene, registry).map_err(|e| write_error(e.to_string())),
+    }
+}
+
+// decodes a scene file read from path, picking the format by its extension. components saved
+// by older versions are upgraded with migrations
+pub fn parse_scene(path: &str, bytes: &[u8], registry: &TypeRegistryArc, migrations: &MigrationRegistry) -> Result<DynamicScene, EngineError> {
+    match SceneFormat::from_path(path) {
+        SceneFormat::Ron => parse_ron(path, bytes, registry, migrations),
+        SceneFormat::Binary => parse_binary(path, bytes, registry, migrations),
+    }
+}
+
+fn parse_ron(path: &str, bytes: &[u8], registry: &TypeRegistryArc, migrations: &MigrationRegistry) -> Result<DynamicScene, EngineError> {
+    let read_error = |reason: String| EngineError::SceneRead{
+        path: String::from(path),
+        reason,
+    };
+    let ron_str = std::str::from_utf8(bytes).map_err(|e| read_error(e.to_string()))?;
+    // new projects start with an empty scene file
+    if ron_str.trim().is_empty() {
+        return Ok(DynamicScene::default());
+    }
+    let mut deserializer = ron::de::Deserializer::from_str(ron_str).map_err(|e| read_error(e.to_string()))?;
+    let scene_deserializer = SceneDeserializer {
+        type_registry: &*registry.read(),
+        migrations: Some(migrations),
+    };
+    scene_deserializer.deserialize(&mut deserializer).map_err(|e| EngineError::SceneDeserialization{
+        path: String::from(path),
+        reason: e.to_string(),
+    })
+}
+
+// components in binary scenes can be skipped but not read loosely, so ones whose fields were
+// added or removed since are left out. convert ron sources saved with the current schemas for
+// shipping
+fn parse_binary(path: &str, bytes: &[u8], registry: &TypeRegistryArc, migrations: &MigrationRegistry) -> Result<DynamicScene, EngineError> {
+    let read_error = |reason: &str| EngineError::SceneRead{
+        path: String::from(path),
+        reason: String::from(reason),
+    };
+    if bytes.is_empty() {
+        return Ok(DynamicScene::default());
+    }
+    if bytes.len() < BINARY_SCENE_HEADER_LEN || &bytes[0..4] != BINARY_SCENE_MAGIC {
+        return Err(read_error("not a binary scene"));
+    }
+    let mut len_bytes = [0u8; 8];
+    len_bytes.copy_from_slice(&bytes[4..BINARY_SCENE_HEADER_LEN]);
+    let payload = &bytes[BINARY_SCENE_HEADER_LEN..];
+    if u64::from_le_bytes(len_bytes) != payload.len() as u64 {
+        return Err(read_error("the file is truncated or has trailing data"));
+    }
+    let scene_deserializer = SceneDeserializer {
+        type_registry: &*registry.read(),
+        migrations: Some(migrations),
+    };
+    bincode_options().deserialize_seed(scene_deserializer, payload).map_err(|e| EngineError::SceneDeserialization{
+        path: String::from(path),
+        reason: e.to_string(),
+    })
+}
+
+// reads a scene and writes it back out in the format of the output path, migrated to the
+// current schemas
+pub fn convert_scene(input: &str, output: &str, registry: &TypeRegistryArc, migrations: &MigrationRegistry) -> Result<(), EngineError> {
+    let bytes = std::fs::read(input).map_err(|e| EngineError::SceneRead{
+        path: String::from(input),
+        reason: e.to_string(),
+    })?;
+    let scene = parse_scene(input, &bytes, registry, migrations)?;
+    scene.write_to_file(output, registry)
+}
@@ -0,0 +1,38 @@
+use std::any::TypeId;
+
+use bevy_ecs::reflect::ReflectResource;
+use bevy_ecs::system::Resource;
+use bevy_ecs::world::FromWorld;
+use bevy_reflect::{FromType, GetTypeRegistration, Reflect, TypeRegistration, TypeRegistry};
+
+// type data saying whether scenes save a resource. resources registered with
+// #[reflect(Resource)] are saved unless this says otherwise, so state that's rebuilt at runtime
+// or belongs to the editor rather than the scene can be kept out
+#[derive(Clone, Copy, Debug)]
+pub struct SaveWithScene(pub bool);
+
+// saves a resource with the scene, even one that wasn't declared with #[reflect(Resource)]
+pub fn save_resource_with_scene<T: Resource + Reflect + FromWorld + GetTypeRegistration>(registry: &mut TypeRegistry){
+    registry.register::<T>();
+    let registration = registry.get_mut(TypeId::of::<T>()).unwrap();
+    if registration.data::<ReflectResource>().is_none() {
+        registration.insert(<ReflectResource as FromType<T>>::from_type());
+    }
+    registration.insert(SaveWithScene(true));
+}
+
+// keeps a reflected resource out of saved scenes
+pub fn skip_resource_in_scene<T: 'static>(registry: &mut TypeRegistry){
+    match registry.get_mut(TypeId::of::<T>()) {
+        Some(registration) => registration.insert(SaveWithScene(false)),
+        None => log::error!(
+            "Can't keep {} out of scenes. It isn't registered.",
+            std::any::type_name::<T>(),
+        ),
+    }
+}
+
+pub fn is_scene_resource(registration: &TypeRegistration) -> bool {
+    registration.data::<ReflectResource>().is_some()
+        && registration.data::<SaveWithScene>().map_or(true, |save| save.0)
+}
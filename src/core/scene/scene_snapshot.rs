@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use bevy_ecs::{
+    entity::{Entity, EntityMap},
+    world::World,
+};
+use bevy_hierarchy::{BuildWorldChildren, Children, DespawnRecursiveExt};
+
+use crate::core::plugins::components::AppInterfaceFlag;
+use crate::core::scene::{
+    DynamicScene,
+    DynamicSceneBuilder,
+    TypeRegistryResource,
+    serialization::SceneSpawnError,
+};
+
+// a copy of every non editor entity and scene resource in a world. taken when play starts and written back
+// when it stops so nothing that happened while playing sticks around
+pub struct SceneSnapshot{
+    scene: DynamicScene,
+}
+
+impl SceneSnapshot{
+    pub fn capture(world: &World) -> Self {
+        let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+        let interface = interface_entities(world);
+
+        let mut builder = DynamicSceneBuilder::from_world_with_type_registry(world, type_registry);
+        builder.extract_entities(world.iter_entities().filter(|e| !interface.contains(e)));
+        builder.extract_resources();
+        SceneSnapshot{
+            scene: builder.build(),
+        }
+    }
+
+    // despawns everything but the editor entities and spawns the snapshot in their place
+    pub fn restore(&self, world: &mut World) -> Result<(), SceneSpawnError> {
+        let interface = interface_entities(world);
+        let scene_entities: HashSet<Entity> = world
+            .iter_entities()
+            .filter(|e| !interface.contains(e))
+            .collect();
+        // editor entities under scene entities are taken out of the hierarchy first, so they
+        // aren't despawned with them or left with a parent that's gone
+        for entity in scene_entities.iter() {
+            let editor_children: Vec<Entity> = world
+                .get::<Children>(*entity)
+                .map(|children| children.iter().copied().filter(|c| interface.contains(c)).collect())
+                .unwrap_or_default();
+            if !editor_children.is_empty() {
+                world.entity_mut(*entity).remove_children(&editor_children);
+            }
+        }
+        for entity in scene_entities {
+            // already gone if it was under another scene entity
+            if world.get_entity(entity).is_some() {
+                world.entity_mut(entity).despawn_recursive();
+            }
+        }
+
+        let mut entity_map = EntityMap::default();
+        self.scene.write_to_world(world, &mut entity_map)
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.scene.entities.len()
+    }
+}
+
+// every AppInterfaceFlag root and everything under it
+pub fn interface_entities(world: &World) -> HashSet<Entity> {
+    let mut interface = HashSet::new();
+    let mut stack: Vec<Entity> = world
+        .iter_entities()
+        .filter(|e| world.get::<AppInterfaceFlag>(*e).is_some())
+        .collect();
+    while let Some(entity) = stack.pop() {
+        if !interface.insert(entity) {
+            continue;
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().copied());
+        }
+    }
+    interface
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::core::scene::SceneFormat;
+
+// how often the scenes directory is checked by default
+pub const SCENE_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+// notices scene files in a directory changing on disk by polling their modification times.
+// polling keeps it working the same on every platform and with editors that save by replacing
+// the file
+pub struct SceneWatcher{
+    dir: PathBuf,
+    interval: Duration,
+    last_poll: Option<Instant>,
+    modified: HashMap<PathBuf, SystemTime>,
+}
+
+impl SceneWatcher{
+    // starts from what's in dir now, so only later changes are reported
+    pub fn new(dir: impl Into<PathBuf>, interval: Duration) -> Self {
+        let dir = dir.into();
+        let modified = scene_modified_times(&dir);
+        SceneWatcher{
+            dir,
+            interval,
+            last_poll: None,
+            modified,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // scene files that were added or modified since the last check. nothing until the interval
+    // has passed since the last one
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if let Some(last_poll) = self.last_poll {
+            if last_poll.elapsed() < self.interval {
+                return Vec::new();
+            }
+        }
+        self.last_poll = Some(Instant::now());
+        self.check()
+    }
+
+    // like poll but ignores the interval
+    pub fn check(&mut self) -> Vec<PathBuf> {
+        let modified = scene_modified_times(&self.dir);
+        let mut changed: Vec<PathBuf> = modified
+            .iter()
+            .filter(|(path, time)| self.modified.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+        self.modified = modified;
+        changed
+    }
+}
+
+fn scene_modified_times(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        // the directory might not be there yet, ie in a new project
+        Err(_) => return HashMap::new(),
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| SceneFormat::is_scene_file(p))
+        .filter_map(|p| {
+            let modified = fs::metadata(&p).and_then(|m| m.modified()).ok()?;
+            Some((p, modified))
+        })
+        .collect()
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use bevy_ecs::prelude::{
+    Resource,
+    Schedule,
+    SystemStage,
+};
+use bevy_ecs::schedule::IntoSystemDescriptor;
+
+// the built in stages other stages can be ordered against
+pub const CAMERA_UPDATE_STAGE: &str = "camera_update";
+pub const MAIN_STAGE: &str = "main";
+pub const UI_STAGE: &str = "ui";
+pub const EVENT_PROCESSING_STAGE: &str = "event_processing";
+pub const UPDATE_STAGE: &str = "update";
+
+// the schedules a scene runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SceneSchedule{
+    // once, when a scene is activated
+    Setup,
+    // at the fixed tick rate
+    Update,
+    // once a frame
+    Render,
+    // once a frame, in the render schedule's ui stages
+    Ui,
+    // once, when a scene is deactivated
+    Teardown,
+}
+
+impl SceneSchedule{
+    // the schedule this one actually lives in
+    pub fn schedule(&self) -> SceneSchedule {
+        match self {
+            SceneSchedule::Ui => SceneSchedule::Render,
+            other => *other,
+        }
+    }
+}
+
+type ScheduleOp = Arc<dyn Fn(&mut Schedule) + Send + Sync>;
+
+// systems and stages registered from outside the engine. they're applied to the schedules of
+// every scene that gets activated, in the order they were registered, so register a stage
+// before adding systems to it
+#[derive(Resource, Clone, Default)]
+pub struct ScheduleRegistry{
+    ops: Vec<(SceneSchedule, ScheduleOp)>,
+}
+
+impl ScheduleRegistry{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_system<Params, S>(&mut self, schedule: SceneSchedule, stage: &'static str, system: S) -> &mut Self
+    where
+        S: IntoSystemDescriptor<Params> + Clone + Send + Sync + 'static
+    {
+        self.push(schedule, move |target: &mut Schedule| {
+            if target.get_stage::<SystemStage>(stage).is_none() {
+                log::error!("Can't add system to stage {}. It doesn't exist.", stage);
+                return;
+            }
+            target.add_system_to_stage(stage, system.clone());
+        })
+    }
+
+    // ui systems go in the "ui" stage, after the panels are laid out
+    pub fn add_ui_system<Params, S>(&mut self, system: S) -> &mut Self
+    where
+        S: IntoSystemDescriptor<Params> + Clone + Send + Sync + 'static
+    {
+        self.add_system(SceneSchedule::Ui, UI_STAGE, system)
+    }
+
+    // adds a stage to the end of the schedule
+    pub fn add_stage<F>(&mut self, schedule: SceneSchedule, label: &'static str, stage: F) -> &mut Self
+    where
+        F: Fn() -> SystemStage + Send + Sync + 'static
+    {
+        self.push(schedule, move |target: &mut Schedule| {
+            if Self::stage_exists(target, label) {
+                return;
+            }
+            target.add_stage(label, stage());
+        })
+    }
+
+    pub fn add_stage_before<F>(&mut self, schedule: SceneSchedule, before: &'static str, label: &'static str, stage: F) -> &mut Self
+    where
+        F: Fn() -> SystemStage + Send + Sync + 'static
+    {
+        self.push(schedule, move |target: &mut Schedule| {
+            if Self::stage_exists(target, label) || !Self::target_exists(target, before) {
+                return;
+            }
+            target.add_stage_before(before, label, stage());
+        })
+    }
+
+    pub fn add_stage_after<F>(&mut self, schedule: SceneSchedule, after: &'static str, label: &'static str, stage: F) -> &mut Self
+    where
+        F: Fn() -> SystemStage + Send + Sync + 'static
+    {
+        self.push(schedule, move |target: &mut Schedule| {
+            if Self::stage_exists(target, label) || !Self::target_exists(target, after) {
+                return;
+            }
+            target.add_stage_after(after, label, stage());
+        })
+    }
+
+    // applies everything registered for schedule to target
+    pub fn apply(&self, schedule: SceneSchedule, target: &mut Schedule){
+        self.apply_since(0, schedule, target);
+    }
+
+    // applies the registrations from index start on. used to add to a scene that's already active
+    pub fn apply_since(&self, start: usize, schedule: SceneSchedule, target: &mut Schedule){
+        for (op_schedule, op) in self.ops.iter().skip(start) {
+            if op_schedule.schedule() == schedule.schedule() {
+                op(target);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    fn push<F>(&mut self, schedule: SceneSchedule, op: F) -> &mut Self
+    where
+        F: Fn(&mut Schedule) + Send + Sync + 'static
+    {
+        self.ops.push((schedule, Arc::new(op)));
+        self
+    }
+
+    fn stage_exists(target: &Schedule, label: &'static str) -> bool {
+        if target.get_stage::<SystemStage>(label).is_some() {
+            log::error!("Can't add stage {}. It already exists.", label);
+            return true;
+        }
+        false
+    }
+
+    fn target_exists(target: &Schedule, label: &'static str) -> bool {
+        if target.get_stage::<SystemStage>(label).is_none() {
+            log::error!("Can't order a stage against {}. It doesn't exist.", label);
+            return false;
+        }
+        true
+    }
+}
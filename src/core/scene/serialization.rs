@@ -1,15 +1,18 @@
 
 use std::fs::File;
 use std::io::Write;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::core::scene::dynamic_scene::{DynamicEntity, DynamicScene};
+use crate::core::scene::migration::{MigrationRegistry, SCENE_FORMAT_VERSION, schema_version};
+use crate::core::scene::scene_format::bincode_options;
+use bincode::Options;
 use bevy_reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
 use bevy_reflect::{serde::UntypedReflectDeserializer, Reflect, TypeRegistry, TypeRegistryArc};
 use bevy_utils::HashSet;
 use serde::ser::SerializeMap;
 use serde::{
-    de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
+    de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
     ser::SerializeStruct,
     Deserialize, Deserializer, Serialize, Serializer,
 };
@@ -40,11 +43,17 @@ pub enum SceneSpawnError {
 }
 
 pub const SCENE_STRUCT: &str = "Scene";
+pub const SCENE_VERSION: &str = "version";
+pub const SCENE_SCHEMAS: &str = "schemas";
+pub const SCENE_RESOURCES: &str = "resources";
 pub const SCENE_ENTITIES: &str = "entities";
 
 pub const ENTITY_STRUCT: &str = "Entity";
 pub const ENTITY_FIELD_COMPONENTS: &str = "components";
 
+// the first scene format version where binary scenes hold each component as its own bytes
+pub const LENGTH_PREFIXED_COMPONENTS_VERSION: u32 = 3;
+
 pub struct SceneSerializer<'a> {
     pub scene: &'a DynamicScene,
     pub registry: &'a TypeRegistryArc,
@@ -61,7 +70,26 @@ impl<'a> Serialize for SceneSerializer<'a> {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct(SCENE_STRUCT, 1)?;
+        // the schema version of every component and resource type in the scene
+        let registry = self.registry.read();
+        let schemas: BTreeMap<&str, u32> = self.scene.entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .chain(self.scene.resources.iter())
+            .map(|component| (component.type_name(), schema_version(&registry, component.type_name())))
+            .collect();
+        drop(registry);
+
+        let mut state = serializer.serialize_struct(SCENE_STRUCT, 4)?;
+        state.serialize_field(SCENE_VERSION, &SCENE_FORMAT_VERSION)?;
+        state.serialize_field(SCENE_SCHEMAS, &schemas)?;
+        state.serialize_field(
+            SCENE_RESOURCES,
+            &ComponentsSerializer {
+                components: &self.scene.resources,
+                registry: self.registry,
+            },
+        )?;
         state.serialize_field(
             SCENE_ENTITIES,
             &EntitiesSerializer {
@@ -129,20 +157,44 @@ impl<'a> Serialize for ComponentsSerializer<'a> {
     where
         S: serde::Serializer,
     {
+        let registry = self.registry.read();
+        let human_readable = serializer.is_human_readable();
         let mut state = serializer.serialize_map(Some(self.components.len()))?;
         for component in self.components {
-            state.serialize_entry(
-                component.type_name(),
-                &TypedReflectSerializer::new(&**component, &self.registry.read()),
-            )?;
+            let value = TypedReflectSerializer::new(&**component, &registry);
+            if human_readable {
+                state.serialize_entry(component.type_name(), &value)?;
+            } else {
+                // binary formats can't skip a value without knowing its type, so each component
+                // is encoded on its own and written with its length
+                let bytes = bincode_options()
+                    .serialize(&value)
+                    .map_err(<S::Error as serde::ser::Error>::custom)?;
+                state.serialize_entry(component.type_name(), &ComponentBytes(&bytes))?;
+            }
         }
         state.end()
     }
 }
 
+// a component encoded by itself
+struct ComponentBytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for ComponentBytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(field_identifier, rename_all = "lowercase")]
 enum SceneField {
+    Version,
+    Schemas,
+    Resources,
     Entities,
 }
 
@@ -154,6 +206,8 @@ enum EntityField {
 
 pub struct SceneDeserializer<'a> {
     pub type_registry: &'a TypeRegistry,
+    // how to upgrade components from older scene files. none loads them as they are
+    pub migrations: Option<&'a MigrationRegistry>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
@@ -165,9 +219,10 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
     {
         deserializer.deserialize_struct(
             SCENE_STRUCT,
-            &[SCENE_ENTITIES],
+            &[SCENE_VERSION, SCENE_SCHEMAS, SCENE_RESOURCES, SCENE_ENTITIES],
             SceneVisitor {
                 type_registry: self.type_registry,
+                migrations: self.migrations,
             },
         )
     }
@@ -175,6 +230,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
 
 struct SceneVisitor<'a> {
     pub type_registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
@@ -188,15 +244,49 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
     where
         A: MapAccess<'de>,
     {
+        // files from before versioning have neither, which is the same as version 0 everywhere
+        let mut version = None;
+        let mut schemas: Option<HashMap<String, u32>> = None;
+        // files from before version 2 don't have resources
+        let mut resources = None;
         let mut entities = None;
         while let Some(key) = map.next_key()? {
             match key {
+                SceneField::Version => {
+                    if version.is_some() {
+                        return Err(Error::duplicate_field(SCENE_VERSION));
+                    }
+                    version = Some(check_version::<A::Error>(map.next_value()?)?);
+                }
+                SceneField::Schemas => {
+                    if schemas.is_some() {
+                        return Err(Error::duplicate_field(SCENE_SCHEMAS));
+                    }
+                    schemas = Some(map.next_value()?);
+                }
+                SceneField::Resources => {
+                    if resources.is_some() {
+                        return Err(Error::duplicate_field(SCENE_RESOURCES));
+                    }
+                    let schemas = schemas.clone().unwrap_or_default();
+                    resources = Some(map.next_value_seed(ComponentDeserializer {
+                        registry: self.type_registry,
+                        migrations: self.migrations,
+                        schemas: &schemas,
+                        version: version.unwrap_or(0),
+                    })?);
+                }
                 SceneField::Entities => {
                     if entities.is_some() {
                         return Err(Error::duplicate_field(SCENE_ENTITIES));
                     }
+                    // schemas are written first, so they're known by the time entities are read
+                    let schemas = schemas.clone().unwrap_or_default();
                     entities = Some(map.next_value_seed(SceneEntitiesDeserializer {
                         type_registry: self.type_registry,
+                        migrations: self.migrations,
+                        schemas: &schemas,
+                        version: version.unwrap_or(0),
                     })?);
                 }
             }
@@ -204,25 +294,67 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
 
         let entities = entities.ok_or_else(|| Error::missing_field(SCENE_ENTITIES))?;
 
-        Ok(DynamicScene { entities })
+        Ok(DynamicScene {
+            entities,
+            resources: resources.unwrap_or_default(),
+        })
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
+        let version = seq
+            .next_element()?
+            .ok_or_else(|| Error::missing_field(SCENE_VERSION))?;
+        check_version::<A::Error>(version)?;
+        let schemas: HashMap<String, u32> = seq
+            .next_element()?
+            .ok_or_else(|| Error::missing_field(SCENE_SCHEMAS))?;
+        // fields are read in order here, so older layouts have to be told apart by version
+        let resources = if version >= 2 {
+            seq.next_element_seed(ComponentDeserializer {
+                registry: self.type_registry,
+                migrations: self.migrations,
+                schemas: &schemas,
+                version,
+            })?
+            .ok_or_else(|| Error::missing_field(SCENE_RESOURCES))?
+        } else {
+            Vec::new()
+        };
         let entities = seq
             .next_element_seed(SceneEntitiesDeserializer {
                 type_registry: self.type_registry,
+                migrations: self.migrations,
+                schemas: &schemas,
+                version,
             })?
             .ok_or_else(|| Error::missing_field(SCENE_ENTITIES))?;
 
-        Ok(DynamicScene { entities })
+        Ok(DynamicScene {
+            entities,
+            resources,
+        })
     }
 }
 
+fn check_version<E: Error>(version: u32) -> Result<u32, E> {
+    if version > SCENE_FORMAT_VERSION {
+        return Err(Error::custom(format!(
+            "scene format version {version} is newer than this engine's ({SCENE_FORMAT_VERSION})"
+        )));
+    }
+    Ok(version)
+}
+
 pub struct SceneEntitiesDeserializer<'a> {
     pub type_registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    // schema versions the file was saved with
+    pub schemas: &'a HashMap<String, u32>,
+    // scene format version the file was saved with
+    pub version: u32,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
@@ -234,12 +366,18 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
     {
         deserializer.deserialize_map(SceneEntitiesVisitor {
             type_registry: self.type_registry,
+            migrations: self.migrations,
+            schemas: self.schemas,
+            version: self.version,
         })
     }
 }
 
 struct SceneEntitiesVisitor<'a> {
     pub type_registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    pub schemas: &'a HashMap<String, u32>,
+    pub version: u32,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
@@ -258,6 +396,9 @@ impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
             let entity = map.next_value_seed(SceneEntityDeserializer {
                 id,
                 type_registry: self.type_registry,
+                migrations: self.migrations,
+                schemas: self.schemas,
+                version: self.version,
             })?;
             entities.push(entity);
         }
@@ -269,6 +410,9 @@ impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
 pub struct SceneEntityDeserializer<'a> {
     pub id: u32,
     pub type_registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    pub schemas: &'a HashMap<String, u32>,
+    pub version: u32,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
@@ -284,6 +428,9 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
             SceneEntityVisitor {
                 id: self.id,
                 registry: self.type_registry,
+                migrations: self.migrations,
+                schemas: self.schemas,
+                version: self.version,
             },
         )
     }
@@ -292,6 +439,9 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
 struct SceneEntityVisitor<'a> {
     pub id: u32,
     pub registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    pub schemas: &'a HashMap<String, u32>,
+    pub version: u32,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
@@ -308,6 +458,9 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
         let components = seq
             .next_element_seed(ComponentDeserializer {
                 registry: self.registry,
+                migrations: self.migrations,
+                schemas: self.schemas,
+                version: self.version,
             })?
             .ok_or_else(|| Error::missing_field(ENTITY_FIELD_COMPONENTS))?;
 
@@ -331,6 +484,9 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
 
                     components = Some(map.next_value_seed(ComponentDeserializer {
                         registry: self.registry,
+                        migrations: self.migrations,
+                        schemas: self.schemas,
+                        version: self.version,
                     })?);
                 }
             }
@@ -348,6 +504,9 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
 
 pub struct ComponentDeserializer<'a> {
     pub registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    pub schemas: &'a HashMap<String, u32>,
+    pub version: u32,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for ComponentDeserializer<'a> {
@@ -357,14 +516,61 @@ impl<'a, 'de> DeserializeSeed<'de> for ComponentDeserializer<'a> {
     where
         D: serde::Deserializer<'de>,
     {
+        let length_prefixed = !deserializer.is_human_readable()
+            && self.version >= LENGTH_PREFIXED_COMPONENTS_VERSION;
         deserializer.deserialize_map(ComponentVisitor {
             registry: self.registry,
+            migrations: self.migrations,
+            schemas: self.schemas,
+            length_prefixed,
         })
     }
 }
 
 struct ComponentVisitor<'a> {
     pub registry: &'a TypeRegistry,
+    pub migrations: Option<&'a MigrationRegistry>,
+    pub schemas: &'a HashMap<String, u32>,
+    // binary scenes from LENGTH_PREFIXED_COMPONENTS_VERSION on
+    pub length_prefixed: bool,
+}
+
+impl<'a> ComponentVisitor<'a> {
+    // a component from a binary scene. its bytes are already read, so removed, unknown and
+    // unmigratable components can be skipped without losing the rest of the scene
+    fn binary_component<E: Error>(&self, key: &str, type_name: &str, bytes: &[u8]) -> Result<Option<Box<dyn Reflect>>, E> {
+        if self.migrations.map_or(false, |m| m.is_removed(type_name)) {
+            return Ok(None);
+        }
+        let registration = match self.registry.get_with_name(type_name) {
+            Some(r) => r,
+            None => {
+                log::warn!("Skipping unknown component {}. It isn't registered.", key);
+                return Ok(None);
+            }
+        };
+
+        let saved_version = self.schemas.get(key).copied().unwrap_or(0);
+        let needs_migration = self.migrations.map_or(false, |m| m.needs_migration(type_name, saved_version));
+        let component = bincode_options()
+            .reject_trailing_bytes()
+            .deserialize_seed(TypedReflectDeserializer::new(registration, self.registry), bytes);
+        match component {
+            Ok(component) => Ok(Some(component)),
+            // binary values can't be read loosely. renamed fields still line up, but added or
+            // removed ones need the scene converted again from its ron source
+            Err(e) if needs_migration => {
+                log::warn!(
+                    "Skipping component {}. Its schema version {} can't be migrated in a binary scene: {}",
+                    key,
+                    saved_version,
+                    e,
+                );
+                Ok(None)
+            },
+            Err(e) => Err(E::custom(format!("couldn't read `{type_name}`: {e}"))),
+        }
+    }
 }
 
 impl<'a, 'de> Visitor<'de> for ComponentVisitor<'a> {
@@ -385,13 +591,46 @@ impl<'a, 'de> Visitor<'de> for ComponentVisitor<'a> {
                 return Err(Error::custom(format!("duplicate component: `{key}`")));
             }
 
-            let registration = self
-                .registry
-                .get_with_name(key)
-                .ok_or_else(|| Error::custom(format!("no registration found for `{key}`")))?;
-            components.push(
-                map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?,
-            );
+            let type_name = match self.migrations {
+                Some(migrations) => migrations.resolve_type_name(key),
+                None => String::from(key),
+            };
+            if self.length_prefixed {
+                let bytes: &[u8] = map.next_value()?;
+                if let Some(component) = self.binary_component(key, &type_name, bytes)? {
+                    components.push(component);
+                }
+                continue;
+            }
+            if self.migrations.map_or(false, |m| m.is_removed(&type_name)) {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            }
+            // one unknown component shouldn't lose the rest of the scene
+            let registration = match self.registry.get_with_name(&type_name) {
+                Some(r) => r,
+                None => {
+                    log::warn!("Skipping unknown component {}. It isn't registered.", key);
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+
+            let saved_version = self.schemas.get(key).copied().unwrap_or(0);
+            match self.migrations {
+                Some(migrations) if migrations.needs_migration(&type_name, saved_version) => {
+                    // read it loosely first so renamed and removed fields don't fail it
+                    let mut value: ron::Value = map.next_value()?;
+                    migrations.migrate(&type_name, saved_version, &mut value);
+                    let component = TypedReflectDeserializer::new(registration, self.registry)
+                        .deserialize(value)
+                        .map_err(|e| Error::custom(format!("couldn't migrate `{type_name}`: {e}")))?;
+                    components.push(component);
+                }
+                _ => components.push(
+                    map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?,
+                ),
+            }
         }
 
         Ok(components)
@@ -0,0 +1,67 @@
+use bevy_ecs::entity::Entity;
+use bevy_hierarchy::{Children, Parent};
+use bevy_reflect::{TypeRegistration, TypeRegistry};
+use bevy_ecs::reflect::{ReflectComponent, ReflectResource};
+use ember_math::{Matrix4f, Vector3f, Vector4f};
+use smallvec::SmallVec;
+
+// registers types with every scene's type registry. made by register_type!, never by hand
+pub struct TypeRegistrar(pub fn(&mut TypeRegistry));
+
+inventory::collect!(TypeRegistrar);
+
+// registers reflected components, resources and value types with every scene. goes next to the
+// type's definition, so adding a type to the engine is the only step there is. works in any crate
+// linked into the engine's binary. plugin libraries are loaded with their own copy of the engine,
+// so what they register this way never reaches the application and they use
+// Plugin::register_types instead
+#[macro_export]
+macro_rules! register_type {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::inventory::submit! {
+            $crate::core::scene::type_registration::TypeRegistrar(|registry| {
+                $(registry.register::<$ty>();)+
+            })
+        }
+    };
+}
+
+// math and hierarchy types used in fields
+register_type!(Vector3f, Vector4f, Matrix4f, Entity, SmallVec<[Entity; 8]>, Parent, Children);
+
+// registers every type that registered itself with register_type!
+pub fn register_engine_types(registry: &mut TypeRegistry){
+    for registrar in inventory::iter::<TypeRegistrar> {
+        (registrar.0)(registry);
+    }
+}
+
+// what a registered type is to the ecs. used to group types in the editor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RegisteredTypeKind{
+    Component,
+    Resource,
+    Value,
+}
+
+impl RegisteredTypeKind{
+    pub fn of(registration: &TypeRegistration) -> Self {
+        if registration.data::<ReflectComponent>().is_some() {
+            RegisteredTypeKind::Component
+        } else if registration.data::<ReflectResource>().is_some() {
+            RegisteredTypeKind::Resource
+        } else {
+            RegisteredTypeKind::Value
+        }
+    }
+}
+
+// every registered type by kind, then by name
+pub fn registered_types(registry: &TypeRegistry) -> Vec<(RegisteredTypeKind, String)> {
+    let mut types: Vec<(RegisteredTypeKind, String)> = registry
+        .iter()
+        .map(|registration| (RegisteredTypeKind::of(registration), String::from(registration.type_name())))
+        .collect();
+    types.sort();
+    types
+}
@@ -1,65 +1,87 @@
-use bevy_ecs::{world::World, system::Commands};
-use bevy_hierarchy::AddChild;
+use bevy_ecs::{world::World, entity::Entity};
+use bevy_hierarchy::{BuildWorldChildren, DespawnRecursiveExt};
 
 use crate::core::plugins::components::{
     AppInterfaceFlag,
     ui::main_menu_component::{
-        EditorUiState, EntityInspectorComponent, UiPanelComponent
+        EntityInspectorComponent, UiPanelComponent, ComponentLibraryComponent
     },
     FileSubMenuComponent,
-    SceneGraphComponent
+    SceneGraphComponent,
+    LoadingScreenComponent,
+    PlayControlsComponent,
+    ProjectPickerComponent,
 };
+use crate::core::project::RecentProjects;
 
-pub fn initalize_editor_interface(
-    mut commands: Commands
-){
-    log::info!("Setting up editor interface");
-    commands.insert_resource(EditorUiState::default());
+// spawns the interface root with the main menu bar, the file menu and the recent projects
+// picker. returns the root so the caller can despawn the whole thing later
+pub fn spawn_main_menu_interface(world: &mut World) -> Entity {
+    log::info!("Setting up main menu interface");
+    let app_interface_entity = spawn_main_menu(world).0;
+    let project_picker_entity = world.spawn(ProjectPickerComponent::default()).id();
+    world.entity_mut(app_interface_entity).push_children(&[project_picker_entity]);
+    app_interface_entity
+}
 
-    let app_interface_entity = commands.spawn_empty().insert(AppInterfaceFlag::default()).id();
-    let main_menu_entity = commands.spawn_empty().insert(UiPanelComponent::top()).id();
-    let left_panel_component = commands.spawn_empty().insert(UiPanelComponent::left()).id();
-    let right_panel_component = commands.spawn_empty().insert(UiPanelComponent::right()).id();
-    let bottom_panel_component = commands.spawn_empty().insert(UiPanelComponent::bottom()).id();
-    let file_sub_menu_entity = commands.spawn_empty().insert(FileSubMenuComponent::default()).id();
-    let scene_graph_entity = commands.spawn_empty().insert(SceneGraphComponent::default()).id();
-    let entity_inspector_entity = commands.spawn_empty().insert(EntityInspectorComponent::default()).id();
+// returns the interface root and the top panel so more can be added to the menu bar
+fn spawn_main_menu(world: &mut World) -> (Entity, Entity) {
+    let app_interface_entity = world.spawn(AppInterfaceFlag::default()).id();
+    let main_menu_entity = world.spawn(UiPanelComponent::top()).id();
+    // browse from next to the last project opened
+    let file_sub_menu = world
+        .get_resource::<RecentProjects>()
+        .and_then(|recent| recent.most_recent())
+        .and_then(|project| project.path.parent())
+        .map(|dir| FileSubMenuComponent::starting_at(dir.to_path_buf()))
+        .unwrap_or_default();
+    let file_sub_menu_entity = world.spawn(file_sub_menu).id();
 
     // set parent relationships
+    world.entity_mut(main_menu_entity).push_children(&[file_sub_menu_entity]);
+    world.entity_mut(app_interface_entity).push_children(&[main_menu_entity]);
 
-    commands.add(AddChild{
-        parent: app_interface_entity,
-        child: main_menu_entity
-    });
-
-    commands.add(AddChild{
-        parent: main_menu_entity,
-        child: file_sub_menu_entity
-    });
-
-    commands.add(AddChild{
-        parent: app_interface_entity,
-        child: left_panel_component
-    });
+    (app_interface_entity, main_menu_entity)
+}
 
-    commands.add(AddChild{
-        parent: app_interface_entity,
-        child: right_panel_component
-    });
+// spawns the full editor interface. main menu, play controls, side panels, scene graph, entity
+// inspector and the list of registered types
+pub fn spawn_editor_interface(world: &mut World) -> Entity {
+    log::info!("Setting up editor interface");
+    let (app_interface_entity, main_menu_entity) = spawn_main_menu(world);
+    let play_controls_entity = world.spawn(PlayControlsComponent::default()).id();
+    let left_panel_component = world.spawn(UiPanelComponent::left()).id();
+    let right_panel_component = world.spawn(UiPanelComponent::right()).id();
+    let bottom_panel_component = world.spawn(UiPanelComponent::bottom()).id();
+    let scene_graph_entity = world.spawn(SceneGraphComponent::default()).id();
+    let entity_inspector_entity = world.spawn(EntityInspectorComponent::default()).id();
+    let component_library_entity = world.spawn(ComponentLibraryComponent::default()).id();
 
-    commands.add(AddChild{
-        parent: app_interface_entity,
-        child: bottom_panel_component
-    });
+    // set parent relationships
+    world.entity_mut(left_panel_component).push_children(&[scene_graph_entity]);
+    world.entity_mut(right_panel_component).push_children(&[entity_inspector_entity]);
+    world.entity_mut(bottom_panel_component).push_children(&[component_library_entity]);
+    world.entity_mut(main_menu_entity).push_children(&[play_controls_entity]);
+    world.entity_mut(app_interface_entity).push_children(&[
+        left_panel_component,
+        right_panel_component,
+        bottom_panel_component,
+    ]);
 
-    commands.add(AddChild{
-        parent: left_panel_component,
-        child: scene_graph_entity
-    });
+    app_interface_entity
+}
 
-    commands.add(AddChild{
-        parent: right_panel_component,
-        child: entity_inspector_entity
-    });
+pub fn spawn_loading_interface(world: &mut World) -> Entity {
+    log::info!("Setting up loading interface");
+    let app_interface_entity = world.spawn(AppInterfaceFlag::default()).id();
+    let loading_screen_entity = world.spawn(LoadingScreenComponent::default()).id();
+    world.entity_mut(app_interface_entity).push_children(&[loading_screen_entity]);
+    app_interface_entity
+}
 
-}
\ No newline at end of file
+// despawns an interface root and everything under it
+pub fn despawn_interface(world: &mut World, root: Entity){
+    if world.get_entity(root).is_some() {
+        world.entity_mut(root).despawn_recursive();
+    }
+}
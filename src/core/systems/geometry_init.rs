@@ -1,27 +1,64 @@
 use bevy_ecs::{
     prelude::{
+        Commands,
         Query, 
         Res,
+        World,
     }
 };
-use vulkano::{memory::{allocator::StandardMemoryAllocator}};
-
-use std::sync::Arc;
 use crate::core::rendering::geometries::geometry_primitives::{
     Vertex,
 };
-use crate::core::plugins::components::geometry_component::{GeometryComponent, GeometryType};
+use crate::core::rendering::geometries::mesh::load_mesh_file;
+use crate::core::plugins::components::geometry_component::{GeometryComponent, GeometryType, MeshSource};
 use crate::core::managers::render_manager::VulkanAllocators;
+use crate::core::project::CurrentProject;
+use crate::core::errors::EngineError;
+use crate::core::events::error_events::ErrorEvent;
 pub struct GeometryInitHelper;
 
 impl GeometryInitHelper{
-    fn create_geometry(mut geom: &mut GeometryComponent, memory_allocator: Arc<StandardMemoryAllocator>){
-        match geom.geometry_type{
-            GeometryType::Box => GeometryInitHelper::init_cube(&mut geom),
-            GeometryType::Triangle => GeometryInitHelper::init_triangle(&mut geom),
-            GeometryType::Plane => GeometryInitHelper::init_plane(&mut geom),
+    // a mesh that can't be loaded is drawn as a box so it can still be found and fixed
+    fn create_geometry(mut geom: &mut GeometryComponent, project: Option<&CurrentProject>) -> Result<(), EngineError> {
+        let result = match geom.geometry_type.clone() {
+            GeometryType::Box => {
+                GeometryInitHelper::init_cube(&mut geom);
+                Ok(())
+            },
+            GeometryType::Triangle => {
+                GeometryInitHelper::init_triangle(&mut geom);
+                Ok(())
+            },
+            GeometryType::Plane => {
+                GeometryInitHelper::init_plane(&mut geom);
+                Ok(())
+            },
+            GeometryType::Mesh(source) => GeometryInitHelper::init_mesh(&mut geom, &source, project),
+        };
+        if result.is_err() {
+            GeometryInitHelper::init_cube(&mut geom);
+        }
+        result
+    }
+
+    // mesh files are relative to the open project
+    fn init_mesh(geom: &mut GeometryComponent, source: &MeshSource, project: Option<&CurrentProject>) -> Result<(), EngineError> {
+        let mesh = match source {
+            MeshSource::Inline(mesh) => {
+                mesh.validate().map_err(|reason| EngineError::Mesh{ path: String::from("inline mesh"), reason })?;
+                mesh.clone()
+            },
+            MeshSource::File(path) => {
+                let path = match project {
+                    Some(project) => project.path.join(path).to_string_lossy().to_string(),
+                    None => path.clone(),
+                };
+                load_mesh_file(&path)?
+            },
         };
-        geom.initialize(memory_allocator.clone());
+        geom.vertices = mesh.vertices();
+        geom.indices = mesh.indices;
+        Ok(())
     }
 
     fn init_cube(mut geom: &mut GeometryComponent){
@@ -87,15 +124,24 @@ impl GeometryInitHelper{
 }
 
 
+// builds every geometry's vertices from its type and uploads them. runs after a scene is
+// loaded, since only the type is saved
 pub fn GeometryInitializerSystem(
     mut query: Query<&mut GeometryComponent>,
     allocators: Res<VulkanAllocators>,
+    project: Option<Res<CurrentProject>>,
+    mut commands: Commands,
 )
 {
     let memory_allocator = allocators.memory_allocator();
     log::debug!("Running geometry init system...");
     for mut geometry in query.iter_mut() {
-        GeometryInitHelper::create_geometry(&mut geometry, memory_allocator.clone());
+        if let Err(e) = GeometryInitHelper::create_geometry(&mut geometry, project.as_deref()) {
+            log::error!("{}", e);
+            commands.add(move |world: &mut World| {
+                world.send_event(ErrorEvent(e));
+            });
+        }
         geometry.initialize(memory_allocator.clone());
     }
 }
\ No newline at end of file
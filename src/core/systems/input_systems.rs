@@ -2,27 +2,30 @@ use crate::core::{plugins::components::{CameraComponent}, managers::input_manage
 use winit::event::VirtualKeyCode;
 
 use crate::core::managers::input_manager::KeyInputQueue;
+use crate::core::application::Time;
 use bevy_ecs::prelude::{Query, Res, ResMut};
 // pub struct CameraMoveSystem;
 
 use ember_math::{Matrix4f, Vector3f};
 
+// editor camera speed in units per second
+const CAMERA_MOVE_SPEED: f32 = 5.0;
 
 pub fn CameraMoveSystem(
     mut query: Query<&mut CameraComponent>,
     input_queue: Res<KeyInputQueue>,
-    mouse_state: Res<MouseState>
+    mouse_state: Res<MouseState>,
+    time: Res<Time>,
 ) {
-    let mut input = input_queue.queue.clone();
     let mut mouse_state = mouse_state.clone();
     for mut cam in query.iter_mut() {
         let mut forward = (cam.eye - cam.look_at).normalize();
         forward.y = 0.0;
         let mut right = forward.cross(cam.up).normalize();
         right.y = 0.0;
-        let keys = input.drain(..);
-        let delta = 0.2;
-        for key in keys{
+        // raw delta so the editor camera still moves while the simulation is paused
+        let delta = CAMERA_MOVE_SPEED * time.raw_delta_seconds();
+        for key in input_queue.held.iter(){
             match key {
                 VirtualKeyCode::W => {
                     cam.eye = cam.eye - (forward.scale(delta));
@@ -6,10 +6,12 @@ pub mod terrain_systems;
 pub mod geometry_init;
 pub mod project_systems;
 pub mod engine_init_systems;
+pub mod physics_systems;
 
 pub use render_systems::DirectionalLightingSystem;
 pub use render_systems::RequiresGraphicsPipeline;
 pub use render_systems::RenderableAssemblyStateModifierSystem;
+pub use render_systems::rendering_enabled;
 
 pub use geometry_init::GeometryInitializerSystem;
 
@@ -17,6 +19,7 @@ pub use ui_systems::CameraUiSystem;
 pub use ui_systems::TransformUiSystem;
 pub use ui_systems::FileSubMenuSystem;
 pub use ui_systems::ShowNewProjectWindow;
+pub use ui_systems::ShowSaveAsWindow;
 pub use ui_systems::ShowOpenProjectWindow;
 
 pub use camera_init_system::CameraInitSystem;
@@ -30,5 +33,11 @@ pub use terrain_systems::TerrainUpdateSystem;
 pub use project_systems::SceneSerializationSystem;
 pub use project_systems::ProjectCreationSystem;
 pub use project_systems::OpenProjectSystem;
+pub use project_systems::PrefabSystem;
 
-pub use engine_init_systems::initalize_editor_interface;
\ No newline at end of file
+pub use engine_init_systems::spawn_main_menu_interface;
+pub use engine_init_systems::spawn_editor_interface;
+pub use engine_init_systems::spawn_loading_interface;
+pub use engine_init_systems::despawn_interface;
+
+pub use physics_systems::VelocitySystem;
\ No newline at end of file
@@ -0,0 +1,22 @@
+use bevy_ecs::prelude::{
+    Query,
+    Res,
+};
+
+use crate::core::application::time::Time;
+use crate::core::plugins::components::{
+    TransformComponent,
+    VelocityComponent,
+};
+
+// integrates velocity into position once per fixed tick
+pub fn VelocitySystem(
+    mut query: Query<(&mut TransformComponent, &VelocityComponent)>,
+    time: Res<Time>,
+){
+    log::debug!("Velocity system...");
+    let dt = time.fixed_delta_seconds();
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.global_position = transform.global_position + velocity.velocity.scale(dt);
+    }
+}
@@ -1,11 +1,18 @@
 
-use crate::core::scene::DynamicScene;
+use crate::core::scene::DynamicSceneBuilder;
+use crate::core::scene::scene_snapshot::interface_entities;
 use crate::core::managers::SceneManagerMessagePump;
 use crate::core::events::scene_manager_messages::SceneManagerMessage;
+use crate::core::plugins::components::{
+    FileSubMenuComponent,
+    SceneMemberComponent,
+    ui::main_menu_component::UiPanelComponent,
+};
+use crate::core::project::{CurrentProject, check_scene_name, scene_file_name};
+use crate::core::project::project_template::create_project;
 
-use std::fs::File;
 use bevy_ecs::prelude::{
-    Query, 
+    Commands,
     World,
     Res,
     ResMut,
@@ -14,56 +21,115 @@ use bevy_ecs::entity::{
     Entity,
 };
 
-use bevy_ecs::prelude::EventReader;
+use bevy_ecs::prelude::{EventReader, EventWriter};
 
 use crate::core::scene::TypeRegistryResource;
 
-use crate::core::events::project_events::{SaveEvent, CreateProjectEvent, OpenProjectEvent};
+use crate::core::events::project_events::{SaveEvent, SaveAsEvent, CreateProjectEvent, OpenProjectEvent};
+use crate::core::events::prefab_events::{SavePrefabEvent, InstantiatePrefabEvent};
+use crate::core::scene::prefab::{save_prefab, instantiate_prefab, prefab_path};
+use crate::core::events::error_events::ErrorEvent;
+use crate::core::errors::EngineError;
 
 // types
 pub struct SerializerData(Vec<String>);
 
+// the entities that belong in a scene file. the editor interface isn't part of the scene, and
+// entities from additively loaded scenes already have a file of their own
+pub fn saveable_entities(world: &World) -> Vec<Entity> {
+    let interface = interface_entities(world);
+    world
+        .iter_entities()
+        .filter(|e| !interface.contains(e))
+        .filter(|e| world.get::<UiPanelComponent>(*e).is_none())
+        .filter(|e| world.get::<FileSubMenuComponent>(*e).is_none())
+        .filter(|e| world.get::<SceneMemberComponent>(*e).is_none())
+        .collect()
+}
+
+// save writes the scene back to the file it came from. save as writes it to a new scene file
+// in the project and makes that the current scene
 pub fn SceneSerializationSystem(
     world: &World,
-    _query: Query<Entity>,
     mut save_events: EventReader<SaveEvent>,
+    mut save_as_events: EventReader<SaveAsEvent>,
     type_registry: Res<TypeRegistryResource>,
+    current_project: Option<Res<CurrentProject>>,
+    mut commands: Commands,
 ){
-    for _event in save_events.iter(){
-        let scene = DynamicScene::from_world(&world, &type_registry.0);
-        scene.write_to_file("./new_save.ron", &type_registry.0);
+    let save = save_events.iter().count() > 0;
+    let save_as = save_as_events.iter().last().map(|e| scene_file_name(&e.scene_name));
+    if !save && save_as.is_none() {
+        return;
+    }
+
+    let mut project = match current_project {
+        Some(p) => p.clone(),
+        None => {
+            log::error!("{}", EngineError::NoProjectOpen);
+            commands.add(|world: &mut World| {
+                world.send_event(ErrorEvent(EngineError::NoProjectOpen));
+            });
+            return;
+        }
+    };
+    let scene_name = save_as.unwrap_or_else(|| project.scene_name.clone());
+    if let Err(e) = check_scene_name(&scene_name) {
+        log::error!("{}", e);
+        commands.add(move |world: &mut World| {
+            world.send_event(ErrorEvent(e));
+        });
+        return;
+    }
+    let scene_path = project.scene_path(&scene_name).to_string_lossy().to_string();
+
+    let mut builder = DynamicSceneBuilder::from_world_with_type_registry(world, type_registry.0.clone());
+    builder.extract_entities(saveable_entities(world).into_iter());
+    builder.extract_resources();
+    let scene = builder.build();
+
+    log::info!("Saving scene to {}...", scene_path);
+    match scene.write_to_file(&scene_path, &type_registry.0) {
+        Ok(()) => {
+            let new_scene = !project.manifest.scenes.contains(&scene_name);
+            if scene_name != project.scene_name || new_scene {
+                project.register_scene(&scene_name);
+                if new_scene {
+                    if let Err(e) = project.manifest.write(&project.path) {
+                        log::error!("{}", e);
+                        commands.add(move |world: &mut World| {
+                            world.send_event(ErrorEvent(e));
+                        });
+                    }
+                }
+                commands.insert_resource(project);
+            }
+        },
+        Err(e) => {
+            log::error!("{}", e);
+            commands.add(move |world: &mut World| {
+                world.send_event(ErrorEvent(e));
+            });
+        }
     }
-    save_events.clear();
 }
 
 pub fn ProjectCreationSystem(
     mut new_project_events: EventReader<CreateProjectEvent>,
-    mut scene_manager_messages: ResMut<SceneManagerMessagePump>
+    mut scene_manager_messages: ResMut<SceneManagerMessagePump>,
+    mut error_events: EventWriter<ErrorEvent>,
 ){
     for event in new_project_events.iter() {
         log::info!("Creating a project");
+        if let Err(e) = create_project(&event.project_path, &event.scene_name) {
+            log::error!("{}", e);
+            error_events.send(ErrorEvent(e));
+            continue;
+        }
 
-        std::fs::create_dir(event.project_path.clone()).expect("Couldn't create project");
-        let mut scenes_folder = format!("{}/scenes", event.project_path.clone());
-        std::fs::create_dir(scenes_folder.clone());
-
-        // basically run cargo init here
-
-        scenes_folder.push_str("/default.ron");
-        match File::create(&scenes_folder) {
-            Err(why) => panic!("couldn't create default ron scene: {}", why),
-            Ok(_file) => (),
-        };
-        let mut project_file = event.project_path.clone();
-        project_file.push_str("/ember.project");
-        match File::create(&project_file){
-            Err(why) => panic!("couldn't create project file {}", why),
-            Ok(_file) => (),
-        };
-        
         let m = SceneManagerMessage::OpenProject {
             path: event.project_path.clone(),
-            scene_name: String::from("default.ron")
+            scene_name: None,
         };
         scene_manager_messages.send(m);
     }
@@ -72,15 +138,52 @@ pub fn ProjectCreationSystem(
 
 pub fn OpenProjectSystem(
     mut open_project_events: EventReader<OpenProjectEvent>,
-    mut scene_manager_messages: ResMut<SceneManagerMessagePump>
+    mut scene_manager_messages: ResMut<SceneManagerMessagePump>,
 ){
     for event in open_project_events.iter() {
         log::info!("Opening a project");
+        // the manifest says which scene to open
         let m = SceneManagerMessage::OpenProject {
             path: event.project_path.clone(),
-            scene_name: String::from("default.ron")
+            scene_name: None,
         };
         scene_manager_messages.send(m);
     }
     open_project_events.clear();
-}
\ No newline at end of file
+}
+
+// prefabs are written and spawned with exclusive world access, so the work is deferred to commands
+pub fn PrefabSystem(
+    mut save_prefab_events: EventReader<SavePrefabEvent>,
+    mut instantiate_prefab_events: EventReader<InstantiatePrefabEvent>,
+    mut commands: Commands,
+){
+    for event in save_prefab_events.iter() {
+        // the name becomes a file name under prefabs, so it can't be a path out of it
+        if let Err(e) = check_scene_name(&event.name) {
+            log::error!("{}", e);
+            commands.add(move |world: &mut World| {
+                world.send_event(ErrorEvent(e));
+            });
+            continue;
+        }
+        let entity = event.entity;
+        let prefab = prefab_path(&event.name);
+        commands.add(move |world: &mut World| {
+            if let Err(e) = save_prefab(world, entity, &prefab) {
+                log::error!("{}", e);
+                world.send_event(ErrorEvent(e));
+            }
+        });
+    }
+    for event in instantiate_prefab_events.iter() {
+        let prefab = event.prefab.clone();
+        let transform = event.transform.clone();
+        commands.add(move |world: &mut World| {
+            if let Err(e) = instantiate_prefab(world, &prefab, transform) {
+                log::error!("{}", e);
+                world.send_event(ErrorEvent(e));
+            }
+        });
+    }
+}
@@ -8,6 +8,7 @@ use bevy_ecs::prelude::{
     ResMut,
     With,
 };
+use bevy_ecs::schedule::ShouldRun;
 use vulkano::command_buffer::allocator::StandardCommandBufferAllocator;
 use vulkano::descriptor_set::{
     allocator::{StandardDescriptorSetAllocator},
@@ -91,6 +92,17 @@ pub trait RequiresGraphicsPipeline{
     fn create_graphics_pipeline(device: Arc<Device>, render_pass: Arc<RenderPass>, viewport: Viewport) -> Arc<GraphicsPipeline>;
 }
 
+// run criteria for anything that touches the gpu. headless applications never get a device
+// so these systems are skipped instead of panicking on missing resources
+pub fn rendering_enabled(
+    device: Option<Res<DeviceResource>>,
+) -> ShouldRun {
+    match device {
+        Some(device) if device.0.is_some() => ShouldRun::Yes,
+        _ => ShouldRun::No,
+    }
+}
+
 
 pub fn RenderableInitializerSystem(
     mut query: Query<&mut RenderableComponent>,
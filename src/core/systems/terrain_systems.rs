@@ -1,8 +1,10 @@
 use bevy_ecs::prelude::{
+    Commands,
     Query,
     Res,
     ResMut,
     With,
+    World,
 };
 use bevy_ecs::event::Events;
 
@@ -21,6 +23,11 @@ use crate::core::plugins::components::CameraMatrices;
 use crate::core::managers::input_manager::KeyInputQueue;
 use crate::core::systems::ui_systems::EguiState;
 use crate::core::plugins::components::TerrainUiComponent;
+use crate::core::plugins::components::TerrainHeightMap;
+use crate::core::rendering::geometries::terrain::{read_height_map, write_height_map};
+use crate::core::project::CurrentProject;
+use crate::core::errors::EngineError;
+use crate::core::events::error_events::ErrorEvent;
 
 use crate::core::events::terrain_events::TerrainRecalculateEvent;
 
@@ -59,18 +66,74 @@ use winit::event::VirtualKeyCode;
 use winit::event::ModifiersState;
 
 use std::sync::{Arc};
+use std::path::Path;
+
+// the baked heights a terrain is built from, if it has any. file references are relative to
+// the open project
+pub fn terrain_heights(terrain: &TerrainComponent, project: Option<&CurrentProject>) -> Result<Option<Vec<f32>>, EngineError> {
+    match &terrain.height_map {
+        TerrainHeightMap::Generated => Ok(None),
+        TerrainHeightMap::Embedded(heights) => Ok(Some(heights.clone())),
+        TerrainHeightMap::File(path) => {
+            let path = match project {
+                Some(project) => project.path.join(path).to_string_lossy().to_string(),
+                None => path.clone(),
+            };
+            read_height_map(&path).map(Some)
+        },
+    }
+}
 
+// the file a terrain's heights are baked to, relative to the project. a terrain baked to a file
+// before keeps it. otherwise the file is named after the scene and numbered past the files already
+// there, so it doesn't depend on the entity's id, which changes every time the scene is loaded
+pub fn baked_heights_path(terrain: &TerrainComponent, project: &CurrentProject) -> String {
+    if let TerrainHeightMap::File(path) = &terrain.height_map {
+        return path.clone();
+    }
+    let scene = Path::new(&project.scene_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (1..)
+        .map(|n| format!("terrain/{}_{}.heights", scene, n))
+        .find(|path| !project.path.join(path).exists())
+        .unwrap()
+}
+
+// builds a terrain's mesh and uploads it. a height map that can't be read is reported and the
+// terrain is generated instead
+fn build_terrain(
+    terrain: &mut TerrainComponent,
+    project: Option<&CurrentProject>,
+    allocators: &VulkanAllocators,
+    commands: &mut Commands,
+){
+    let heights = match terrain_heights(terrain, project) {
+        Ok(h) => h,
+        Err(e) => {
+            log::error!("{}", e);
+            commands.add(move |world: &mut World| {
+                world.send_event(ErrorEvent(e));
+            });
+            None
+        }
+    };
+    terrain.regenerate(heights.as_deref());
+    terrain.initialize(allocators.memory_allocator());
+}
+
+// terrain meshes aren't saved, so they're built from the saved parameters after a load
 pub fn TerrainInitSystem(
     mut query: Query<&mut TerrainComponent>,
     allocators: Res<VulkanAllocators>,
+    project: Option<Res<CurrentProject>>,
+    mut commands: Commands,
 ){
     log::info!("Terrain init system...");
     for mut terrain in query.iter_mut() {
-        {
-            log::info!("Generating Terrain Geometry");
-            terrain.geometry.lock().unwrap().generate_terrain();
-        }
-        terrain.initialize(allocators.memory_allocator());
+        log::info!("Generating Terrain Geometry");
+        build_terrain(&mut terrain, project.as_deref(), &allocators, &mut commands);
     }
 }
 
@@ -78,14 +141,13 @@ pub fn TerrainUpdateSystem(
     mut query: Query<&mut TerrainComponent>,
     mut recalculate_events: ResMut<Events<TerrainRecalculateEvent>>,
     allocators: Res<VulkanAllocators>,
+    project: Option<Res<CurrentProject>>,
+    mut commands: Commands,
 ){
     let mut reader = recalculate_events.get_reader();
     for _event in reader.iter(&recalculate_events){
         for mut terrain in query.iter_mut(){
-            {
-                terrain.geometry.lock().unwrap().generate_terrain();
-            }
-            terrain.initialize(allocators.memory_allocator());
+            build_terrain(&mut terrain, project.as_deref(), &allocators, &mut commands);
         }
     }
     recalculate_events.clear();
@@ -297,17 +359,25 @@ impl TerrainAssemblyStateSystemPipeline {
 }
 
 
+// changing a parameter throws away baked heights, since they were made from the old ones
 pub fn TerrainUiSystem(
     mut query: Query<&mut TerrainComponent, With<TerrainUiComponent>>,
     mut terrain_recalc_events: ResMut<Events<TerrainRecalculateEvent>>,
     egui_state: Res<EguiState>,
+    project: Option<Res<CurrentProject>>,
+    mut commands: Commands,
 ){
     log::debug!("Terrain ui system...");
 
     let ctx = egui_state.ctx.clone();
-    for terrain in query.iter_mut(){
+    for mut terrain in query.iter_mut(){
         let mut size = terrain.get_size();
         let mut amplitude = terrain.get_amplitude();
+        let mut seed = terrain.seed;
+        let mut noise = terrain.noise.clone();
+        let mut bake = false;
+        let mut bake_to_file = false;
+        let mut generate = false;
 
         egui::Window::new("Terrain Settings")
             .show(&ctx, |ui| {
@@ -319,22 +389,80 @@ pub fn TerrainUiSystem(
                     ui.label("Amplidutde");
                     ui.add(egui::Slider::new(&mut amplitude, 0.1..=50.0).step_by(0.1));
                 });
+                ui.horizontal(|ui|{
+                    ui.label("Seed");
+                    ui.add(egui::DragValue::new(&mut seed));
+                });
+                ui.horizontal(|ui|{
+                    ui.label("Frequency");
+                    ui.add(egui::Slider::new(&mut noise.frequency, 0.01..=2.0));
+                });
+                ui.horizontal(|ui|{
+                    ui.label("Octaves");
+                    ui.add(egui::Slider::new(&mut noise.octaves, 1..=8));
+                });
+                ui.horizontal(|ui|{
+                    ui.label("Lacunarity");
+                    ui.add(egui::Slider::new(&mut noise.lacunarity, 1.0..=4.0));
+                });
+                ui.horizontal(|ui|{
+                    ui.label("Persistence");
+                    ui.add(egui::Slider::new(&mut noise.persistence, 0.0..=1.0));
+                });
+                ui.separator();
+                ui.horizontal(|ui|{
+                    let height_map = match &terrain.height_map {
+                        TerrainHeightMap::Generated => String::from("Generated"),
+                        TerrainHeightMap::Embedded(_) => String::from("Baked into scene"),
+                        TerrainHeightMap::File(path) => format!("Baked to {}", path),
+                    };
+                    ui.label(format!("Heights: {}", height_map));
+                });
+                ui.horizontal(|ui|{
+                    bake = ui.button("Bake").clicked();
+                    if project.is_some() {
+                        bake_to_file = ui.button("Bake to File").clicked();
+                    }
+                    if terrain.height_map != TerrainHeightMap::Generated {
+                        generate = ui.button("Generate").clicked();
+                    }
+                });
             });
         if size < 1 {
             size = 1;
         }
-        let size_changed = size != terrain.get_size();
-        if size_changed {
+        let params_changed = size != terrain.get_size()
+            || amplitude != terrain.get_amplitude()
+            || seed != terrain.seed
+            || noise != terrain.noise;
+        if params_changed {
             terrain.set_size(size as usize);
-        }
-        let amplitude_changed = amplitude != terrain.get_amplitude();
-        if amplitude_changed {
             terrain.set_amplitude(amplitude);
+            terrain.seed = seed;
+            terrain.noise = noise;
+        }
+
+        if bake {
+            terrain.bake_height_map();
+        }
+        if let (true, Some(project)) = (bake_to_file, project.as_ref()) {
+            let relative_path = baked_heights_path(&terrain, project);
+            let path = project.path.join(&relative_path).to_string_lossy().to_string();
+            let heights = terrain.geometry.lock().unwrap().baked_heights();
+            match write_height_map(&path, &heights) {
+                Ok(()) => terrain.height_map = TerrainHeightMap::File(relative_path),
+                Err(e) => {
+                    log::error!("{}", e);
+                    commands.add(move |world: &mut World| {
+                        world.send_event(ErrorEvent(e));
+                    });
+                }
+            }
         }
 
-        if amplitude_changed || size_changed {
+        if params_changed || generate {
+            terrain.height_map = TerrainHeightMap::Generated;
             terrain_recalc_events.send(TerrainRecalculateEvent{});
         }
-        // terrain.set_size(size as usize);
     }
-}
\ No newline at end of file
+}
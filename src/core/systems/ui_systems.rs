@@ -1,17 +1,33 @@
-use crate::core::plugins::components::ui::main_menu_component::{ComponentLibraryComponent, EditorUiState, EntityInspectorComponent, UiPanelComponent, PanelType};
+use crate::core::plugins::components::ui::main_menu_component::{ComponentLibraryComponent, EditorUiState, EntityInspectorComponent, UiPanelComponent, PanelType, PlayControlsComponent};
 use crate::core::plugins::components::{
     CameraComponent,
     TransformComponent,
     TransformUiComponent,
     FileSubMenuComponent,
     SceneGraphComponent,
+    LoadingScreenComponent,
+    ProjectPickerComponent,
 };
 use crate::core::events::project_events::{
     SaveEvent,
+    SaveAsEvent,
     CreateProjectEvent,
     CloseProjectEvent,
     OpenProjectEvent,
 };
+use crate::core::events::prefab_events::{SavePrefabEvent, InstantiatePrefabEvent};
+use crate::core::events::play_mode_events::PlayModeEvent;
+use crate::core::events::scene_events::SceneLoadProgressEvent;
+use crate::core::events::error_events::ErrorEvent;
+use crate::core::application::PlayMode;
+use crate::core::project::CurrentProject;
+use crate::core::project::project_manifest::PROJECT_FILE;
+use crate::core::project::RecentProjects;
+use crate::core::project::recent_projects::opened_ago;
+use crate::core::scene::SceneFormat;
+use crate::core::scene::prefab::PREFAB_DIR;
+use crate::core::scene::TypeRegistryResource;
+use crate::core::scene::type_registration::{registered_types, RegisteredTypeKind};
 
 
 use bevy_ecs::system::Commands;
@@ -26,11 +42,13 @@ use egui::Align;
 
 use bevy_ecs::prelude::{
     Res,
+    ResMut,
     Query,
     World,
 };
 
 use bevy_ecs::prelude::EventWriter;
+use bevy_ecs::prelude::EventReader;
 use bevy_ecs::prelude::Resource;
 use bevy_ecs::entity::Entity;
 // use puffin_egui;
@@ -48,6 +66,13 @@ pub struct EguiState{
     pub painter: Painter,
 }
 
+// the ui of the panel an interface entity sits in. None if the entity isn't under a panel or
+// the panel hasn't been laid out yet
+fn parent_panel_ui(world: &World, entity: Entity) -> Option<Arc<Mutex<Ui>>> {
+    let parent_entity = world.get_entity(entity)?.get::<Parent>()?.get();
+    world.get_entity(parent_entity)?.get::<UiPanelComponent>()?.ui.clone()
+}
+
 pub fn PanelInitSystem(
     mut query: Query<(&mut UiPanelComponent, Entity)>,
     egui_state: Res<EguiState>,
@@ -98,11 +123,19 @@ pub fn FileSubMenuSystem(
     mut commands: Commands
 ){
     log::debug!("File Sub Menu System...");
-    let (comp, entity) = query.get_single().unwrap();
+    // states without a main menu don't spawn one
+    let (comp, entity) = match query.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
     let mut send_save = false;
     let mut send_close = false;
-    let parent_entity = world.get_entity(entity).unwrap().get::<Parent>().unwrap().get();
-    let ui_arc = world.get_entity(parent_entity).unwrap().get::<UiPanelComponent>().unwrap().ui.clone().unwrap();
+    let mut open_recent = None;
+    let recent_projects = world.get_resource::<RecentProjects>();
+    let ui_arc = match parent_panel_ui(world, entity) {
+        Some(ui) => ui,
+        None => return,
+    };
     let mut ui = ui_arc.lock().unwrap();
     let _file_ui = ui.menu_button("File", |ui|{
         if ui.button("New").clicked() {
@@ -120,10 +153,31 @@ pub fn FileSubMenuSystem(
             }
             ui.close_menu();
         }
+        ui.menu_button("Recent", |ui|{
+            let projects = recent_projects.map(|r| r.projects.as_slice()).unwrap_or_default();
+            if projects.is_empty() {
+                ui.label("No recent projects");
+            }
+            for project in projects.iter() {
+                let button = ui
+                    .button(project.name.as_str())
+                    .on_hover_text(project.path.to_string_lossy().to_string());
+                if button.clicked() {
+                    open_recent = Some(project.path.to_string_lossy().to_string());
+                    ui.close_menu();
+                }
+            }
+        });
         if ui.button("Save").clicked() {
             send_save = true;
             ui.close_menu();
         }
+        if ui.button("Save As").clicked() {
+            {
+                *comp.save_as_window.lock().unwrap() = true;
+            }
+            ui.close_menu();
+        }
         if ui.button("Close").clicked() {
             send_close = true;
             ui.close_menu();
@@ -141,6 +195,78 @@ pub fn FileSubMenuSystem(
             world.send_event(CloseProjectEvent);
         });
     }
+
+    if let Some(project_path) = open_recent {
+        log::info!("Opening recent project : {}", project_path);
+        commands.add(move |world: &mut World|{
+            world.send_event(OpenProjectEvent{project_path});
+        });
+    }
+}
+
+// lists the recent projects while nothing is open. picking one opens it
+pub fn ProjectPickerUiSystem(
+    mut query: Query<&mut ProjectPickerComponent>,
+    file_menu_query: Query<&FileSubMenuComponent>,
+    recent_projects: Option<Res<RecentProjects>>,
+    egui_state: Res<EguiState>,
+    mut open_project_events: EventWriter<OpenProjectEvent>,
+){
+    let ctx = egui_state.ctx.clone();
+    let projects = recent_projects.as_ref().map(|r| r.projects.as_slice()).unwrap_or_default();
+    for mut comp in query.iter_mut(){
+        let mut open = comp.open;
+        let mut close = false;
+        egui::Window::new("Projects")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(&ctx, |ui| {
+                if projects.is_empty() {
+                    ui.label("No recent projects. Make a new one or open one from disk.");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui|{
+                        for project in projects.iter() {
+                            ui.horizontal(|ui|{
+                                if ui.button("Open").clicked() {
+                                    log::info!("Opening recent project : {:?}", project.path);
+                                    open_project_events.send(OpenProjectEvent{
+                                        project_path: project.path.to_string_lossy().to_string(),
+                                    });
+                                    close = true;
+                                }
+                                ui.vertical(|ui|{
+                                    ui.strong(project.name.as_str());
+                                    ui.label(project.path.to_string_lossy().to_string());
+                                    ui.small(format!("Opened {}", opened_ago(project.opened)));
+                                });
+                            });
+                            ui.separator();
+                        }
+                    });
+                ui.horizontal(|ui|{
+                    // hands over to the file menu's windows
+                    if ui.button("New Project").clicked() {
+                        for file_menu in file_menu_query.iter() {
+                            *file_menu.new_project_window.lock().unwrap() = true;
+                            *file_menu.open_project_window.lock().unwrap() = false;
+                        }
+                        close = true;
+                    }
+                    if ui.button("Open Other").clicked() {
+                        for file_menu in file_menu_query.iter() {
+                            *file_menu.new_project_window.lock().unwrap() = false;
+                            *file_menu.open_project_window.lock().unwrap() = true;
+                        }
+                        close = true;
+                    }
+                });
+            });
+        comp.open = open && !close;
+    }
 }
 
 pub fn ShowNewProjectWindow(
@@ -151,7 +277,15 @@ pub fn ShowNewProjectWindow(
     for mut comp in query.iter_mut(){
         let ctx = egui_state.ctx.clone();
         let mut current_path = comp.current_nav_path.clone();
-        let paths = std::fs::read_dir(&current_path).unwrap();
+        let paths = match std::fs::read_dir(&current_path) {
+            Ok(p) => p,
+            Err(e) => {
+                // somewhere we can't look. go back to where we started
+                log::warn!("Couldn't read {}: {}", current_path.to_string_lossy(), e);
+                comp.current_nav_path = std::env::current_dir().unwrap_or_default();
+                continue;
+            }
+        };
         let mut entry_buf = comp.text_entry.clone();
         let new_project_window_arc = comp.new_project_window.clone();
         let mut new_project_window = new_project_window_arc.lock().unwrap();
@@ -168,7 +302,7 @@ pub fn ShowNewProjectWindow(
                             None => current_path.clone(),
                         };
                     }
-                    ui.label(format!("{}", current_path.to_str().unwrap()))
+                    ui.label(format!("{}", current_path.to_string_lossy()))
                 });
 
                 ui.separator();
@@ -176,14 +310,14 @@ pub fn ShowNewProjectWindow(
                     .max_height(100.0)
                     .max_width(f32::INFINITY)
                     .show(ui, |ui|{
-                        for path in paths {
-                            let p = path.unwrap().path();
+                        for path in paths.flatten() {
+                            let p = path.path();
                             if !p.is_dir(){
                                 continue;
                             }
                             let label = ui.selectable_label(
                                 false,
-                                p.clone().to_str().unwrap(),
+                                p.to_string_lossy().to_string(),
                             );
                             if label.clicked() {
                                 if p.clone().is_dir(){
@@ -205,10 +339,7 @@ pub fn ShowNewProjectWindow(
                         }else{
                             create_project_events.send(
                                 CreateProjectEvent{
-                                    project_path: String::from(
-                                        p.to_str()
-                                            .unwrap()
-                                        ),
+                                    project_path: p.to_string_lossy().to_string(),
                                     scene_name: String::from("default.ron")
                                     }
                                 );
@@ -222,6 +353,42 @@ pub fn ShowNewProjectWindow(
     }
 }
 
+pub fn ShowSaveAsWindow(
+    mut query: Query<&mut FileSubMenuComponent>,
+    egui_state: Res<EguiState>,
+    mut save_as_events: EventWriter<SaveAsEvent>,
+){
+    for mut comp in query.iter_mut(){
+        let ctx = egui_state.ctx.clone();
+        let mut entry_buf = comp.save_as_entry.clone();
+        let mut saved = false;
+        let save_as_window_arc = comp.save_as_window.clone();
+        let mut save_as_window = save_as_window_arc.lock().unwrap();
+        egui::Window::new("Save Scene As")
+            .open(&mut *save_as_window)
+            .show(&ctx.clone(), |ui|{
+                ui.label("Save the scene under a new name in the current project.");
+                ui.horizontal(|ui|{
+                    ui.label("Scene Name : ");
+                    ui.text_edit_singleline(&mut entry_buf);
+                    if ui.button("Save").clicked(){
+                        if entry_buf.trim().is_empty() {
+                            log::warn!("Scene name is empty!");
+                        }else{
+                            save_as_events.send(SaveAsEvent{ scene_name: entry_buf.clone() });
+                            saved = true;
+                        }
+                    }
+                });
+        });
+        if saved {
+            *save_as_window = false;
+            entry_buf.clear();
+        }
+        comp.save_as_entry = entry_buf;
+    }
+}
+
 pub fn ShowOpenProjectWindow(
     mut query: Query<&mut FileSubMenuComponent>,
     egui_state: Res<EguiState>,
@@ -230,7 +397,15 @@ pub fn ShowOpenProjectWindow(
     for mut comp in query.iter_mut(){
         let ctx = egui_state.ctx.clone();
         let mut current_path = comp.current_nav_path.clone();
-        let paths = std::fs::read_dir(&current_path).unwrap();
+        let paths = match std::fs::read_dir(&current_path) {
+            Ok(p) => p,
+            Err(e) => {
+                // somewhere we can't look. go back to where we started
+                log::warn!("Couldn't read {}: {}", current_path.to_string_lossy(), e);
+                comp.current_nav_path = std::env::current_dir().unwrap_or_default();
+                continue;
+            }
+        };
         let entry_buf = comp.text_entry.clone();
         let open_project_window_arc = comp.open_project_window.clone();
         let mut open_project_window = open_project_window_arc.lock().unwrap();
@@ -247,7 +422,7 @@ pub fn ShowOpenProjectWindow(
                             None => current_path.clone(),
                         };
                     }
-                    ui.label(format!("{}", current_path.to_str().unwrap()))
+                    ui.label(format!("{}", current_path.to_string_lossy()))
                 });
 
                 ui.separator();
@@ -255,14 +430,14 @@ pub fn ShowOpenProjectWindow(
                     .max_height(100.0)
                     .max_width(f32::INFINITY)
                     .show(ui, |ui|{
-                        for path in paths {
-                            let p = path.unwrap().path();
+                        for path in paths.flatten() {
+                            let p = path.path();
                             if !p.is_dir(){
                                 continue;
                             }
                             let label = ui.selectable_label(
                                 false,
-                                p.clone().to_str().unwrap(),
+                                p.to_string_lossy().to_string(),
                             );
                             if label.clicked() {
                                 if p.clone().is_dir(){
@@ -274,7 +449,7 @@ pub fn ShowOpenProjectWindow(
                 ui.separator();
                 ui.horizontal(|ui|{
                     ui.label("Selected Project : ");
-                    ui.label(current_path.clone().to_str().unwrap());
+                    ui.label(current_path.to_string_lossy().to_string());
                     if ui.button("Open Project").clicked(){
                         let target = Path::new(&entry_buf);
                         let mut p = current_path.clone();
@@ -283,12 +458,12 @@ pub fn ShowOpenProjectWindow(
                             log::warn!("Project doesn't exist at {:?} ", p.to_str());
                         }else{ // project folder exists
                             let mut proj_file = p.clone();
-                            proj_file.push("ember.project");
+                            proj_file.push(PROJECT_FILE);
                             if !proj_file.exists(){
                                 log::warn!("This is not a valid project. {} Does not exist", proj_file.display());
                             } else {
-                                log::info!("Opening project : {:?}", p.to_str().unwrap());
-                                open_project_events.send(OpenProjectEvent{project_path: String::from(p.to_str().unwrap())});
+                                log::info!("Opening project : {:?}", p);
+                                open_project_events.send(OpenProjectEvent{project_path: p.to_string_lossy().to_string()});
                             }
                         }
                     }
@@ -375,13 +550,15 @@ pub fn SceneGraphUiSystem(
     mut query: Query<(&SceneGraphComponent, Entity)>,
     egui_state: Res<EguiState>,
     world: &World,
+    current_project: Option<Res<CurrentProject>>,
     mut commands: Commands
 ){
 
     for (comp, entity) in query.iter(){
-        let parent_entity = world.get_entity(entity).unwrap().get::<Parent>().unwrap().get();
-        let mut left_panel = world.get_entity(parent_entity).unwrap().get::<UiPanelComponent>().expect("target not found");
-        let ui_arc = left_panel.ui.clone().unwrap().clone();
+        let ui_arc = match parent_panel_ui(world, entity) {
+            Some(ui) => ui,
+            None => continue,
+        };
         let mut left_panel_ui = ui_arc.lock().unwrap();
         
         let ctx = egui_state.ctx.clone();
@@ -400,32 +577,212 @@ pub fn SceneGraphUiSystem(
                 }
             }
         });
+
+        // every prefab in the project, spawned at the origin
+        let project = match &current_project {
+            Some(p) => p,
+            None => continue,
+        };
+        egui::CollapsingHeader::new("Prefabs").show(&mut left_panel_ui, |ui|{
+            let prefabs = match std::fs::read_dir(project.path.join(PREFAB_DIR)) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+            for prefab in prefabs.flatten().map(|p| p.path()).filter(|p| SceneFormat::is_scene_file(p)) {
+                let file_name = prefab.file_name().unwrap().to_string_lossy().to_string();
+                ui.horizontal(|ui|{
+                    ui.label(&file_name);
+                    if ui.button("Spawn").clicked(){
+                        let prefab = format!("{}/{}", PREFAB_DIR, file_name);
+                        commands.add(move |world: &mut World|{
+                            world.send_event(InstantiatePrefabEvent{
+                                prefab,
+                                transform: TransformComponent::create_empty(),
+                            });
+                        });
+                    }
+                });
+            }
+        });
     }
 }
 
-pub fn ComponentLibraryUiSystem(
-    query: Query<&ComponentLibraryComponent>,
+pub fn LoadingScreenUiSystem(
+    mut query: Query<&mut LoadingScreenComponent>,
+    mut progress_events: EventReader<SceneLoadProgressEvent>,
+    egui_state: Res<EguiState>,
+){
+    let ctx = egui_state.ctx.clone();
+    let latest = progress_events.iter().last();
+    for mut comp in query.iter_mut(){
+        if let Some(event) = latest {
+            comp.progress = event.progress;
+            comp.stage = String::from(event.stage);
+        }
+        egui::Window::new("Loading")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(&ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Loading...");
+                });
+                ui.add(egui::ProgressBar::new(comp.progress).text(comp.stage.clone()));
+            });
+    }
+}
+
+pub fn PlayControlsUiSystem(
+    query: Query<(&PlayControlsComponent, Entity)>,
+    play_mode: Res<PlayMode>,
+    world: &World,
+    mut play_mode_events: EventWriter<PlayModeEvent>,
+){
+    let (_comp, entity) = match query.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let ui_arc = match parent_panel_ui(world, entity) {
+        Some(ui) => ui,
+        None => return,
+    };
+    let mut ui = ui_arc.lock().unwrap();
+    ui.horizontal(|ui| {
+        match *play_mode {
+            PlayMode::Editing => {
+                if ui.button("Play").clicked() {
+                    play_mode_events.send(PlayModeEvent::Play);
+                }
+            },
+            PlayMode::Playing => {
+                if ui.button("Pause").clicked() {
+                    play_mode_events.send(PlayModeEvent::Pause);
+                }
+                if ui.button("Stop").clicked() {
+                    play_mode_events.send(PlayModeEvent::Stop);
+                }
+            },
+            PlayMode::Paused => {
+                if ui.button("Resume").clicked() {
+                    play_mode_events.send(PlayModeEvent::Resume);
+                }
+                if ui.button("Step").clicked() {
+                    play_mode_events.send(PlayModeEvent::Step);
+                }
+                if ui.button("Stop").clicked() {
+                    play_mode_events.send(PlayModeEvent::Stop);
+                }
+            },
+            PlayMode::Running => (),
+        }
+    });
+}
+
+// keeps the errors that reached the ui until they're dismissed
+#[derive(Resource, Default)]
+pub struct ErrorLog{
+    pub errors: Vec<String>,
+}
+
+pub fn ErrorUiSystem(
+    mut error_events: EventReader<ErrorEvent>,
+    mut error_log: ResMut<ErrorLog>,
     egui_state: Res<EguiState>,
+){
+    for event in error_events.iter() {
+        error_log.errors.push(event.0.to_string());
+    }
+    if error_log.errors.is_empty() {
+        return;
+    }
+    let ctx = egui_state.ctx.clone();
+    let mut dismissed = false;
+    egui::Window::new("Error")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(&ctx, |ui| {
+            for error in error_log.errors.iter() {
+                ui.label(error);
+            }
+            ui.separator();
+            if ui.button("Ok").clicked() {
+                dismissed = true;
+            }
+        });
+    if dismissed {
+        error_log.errors.clear();
+    }
+}
+
+// every type scenes can save, grouped by what it is to the ecs
+pub fn ComponentLibraryUiSystem(
+    query: Query<(&ComponentLibraryComponent, Entity)>,
+    type_registry: Res<TypeRegistryResource>,
     world: &World,
 ){
+    let (_, entity) = match query.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let ui_arc = match parent_panel_ui(world, entity) {
+        Some(ui) => ui,
+        None => return,
+    };
+    let mut ui = ui_arc.lock().unwrap();
 
+    let types = registered_types(&type_registry.0.read());
+    for (kind, header) in [
+        (RegisteredTypeKind::Component, "Components"),
+        (RegisteredTypeKind::Resource, "Resources"),
+        (RegisteredTypeKind::Value, "Values"),
+    ] {
+        egui::CollapsingHeader::new(header).show(&mut ui, |ui|{
+            for (_, type_name) in types.iter().filter(|(k, _)| *k == kind) {
+                ui.label(type_name.split("::").last().unwrap()).on_hover_text(type_name);
+            }
+        });
+    }
 }
 
 pub fn EntityInspectionUiSystem(
     query: Query<(&EntityInspectorComponent, Entity)>,
     ui_state: Res<EditorUiState>,
     world: &World,
+    mut commands: Commands,
 ){
     if let Some(selected_entity) = ui_state.selected_entity{
 
         // for (comp, entity) in query.iter(){
-        let (_, entity) = query.get_single().unwrap();
-        let parent_entity = world.get_entity(entity).unwrap().get::<Parent>().unwrap().get();
-        let mut right_panel = world.get_entity(parent_entity).unwrap().get::<UiPanelComponent>().unwrap();
-        let ui_arc = right_panel.ui.clone().unwrap().clone();
+        let (inspector, entity) = match query.get_single() {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+        let ui_arc = match parent_panel_ui(world, entity) {
+            Some(ui) => ui,
+            None => return,
+        };
         let mut ui = ui_arc.lock().unwrap();
 
         ui.heading(format!("Selected Entity {}", selected_entity.to_bits()));
+        ui.horizontal(|ui|{
+            let mut prefab_name = inspector.prefab_name.lock().unwrap();
+            ui.text_edit_singleline(&mut *prefab_name);
+            if ui.button("Save as Prefab").clicked(){
+                if prefab_name.trim().is_empty() {
+                    log::warn!("Prefab name is empty!");
+                }else{
+                    let name = prefab_name.clone();
+                    commands.add(move |world: &mut World|{
+                        world.send_event(SavePrefabEvent{
+                            entity: selected_entity,
+                            name,
+                        });
+                    });
+                }
+            }
+        });
         ui.separator();
 
         let archetypes = world.archetypes();
@@ -2,7 +2,13 @@
 pub mod core;
 
 pub use bevy_ecs;
+pub use bevy_reflect;
+pub use inventory;
 pub use crate::core::application::application_manager::Application;
+pub use crate::core::application::ApplicationConfig;
+pub use crate::core::scene::{ScheduleRegistry, SceneSchedule, MigrationRegistry};
+pub use crate::core::errors::EngineError;
+pub use crate::core::plugins::Plugin;
 
 // importing traits i guess
 pub use crate::core::managers::manager::Manager;
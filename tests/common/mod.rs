@@ -0,0 +1,51 @@
+// not every test crate uses every helper
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy_ecs::entity::EntityMap;
+use bevy_ecs::world::World;
+use serde::de::DeserializeSeed;
+
+use ember::core::application::application_manager::Application;
+use ember::core::scene::{DynamicScene, SceneDeserializer, TypeRegistryResource};
+
+// the world saved as a ron scene with its own type registry
+pub fn save_ron(world: &World) -> String {
+    let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+    DynamicScene::from_world(world, &type_registry)
+        .serialize_ron(&type_registry)
+        .expect("couldn't serialize scene")
+}
+
+// reads a ron scene with the world's type registry and spawns it into the world
+pub fn load_ron(world: &mut World, ron_str: &str){
+    let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+    let mut deserializer = ron::de::Deserializer::from_str(ron_str).expect("invalid ron");
+    let scene = SceneDeserializer{ type_registry: &*type_registry.read(), migrations: None }
+        .deserialize(&mut deserializer)
+        .expect("couldn't deserialize scene");
+    scene.write_to_world(world, &mut EntityMap::default()).expect("couldn't spawn scene");
+}
+
+// an empty directory for the test named name, cleared of anything an earlier run left behind
+pub fn temp_project_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ember_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// runs the application a tick at a time until done says so, for work like scene loads that
+// finishes on another thread. false if it gave up after about five seconds
+pub fn run_until(app: &mut Application, mut done: impl FnMut(&Application) -> bool) -> bool {
+    for _ in 0..500 {
+        app.run_headless(1);
+        if done(app) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
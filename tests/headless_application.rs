@@ -0,0 +1,32 @@
+use ember_math::Vector3f;
+use log::LevelFilter;
+
+use ember::core::application::application_manager::Application;
+use ember::core::application::Time;
+use ember::core::plugins::components::{TransformBuilder, TransformComponent, VelocityComponent};
+
+#[test]
+fn headless_ticks_run_the_update_schedule(){
+    let mut app = Application::create_headless_application(LevelFilter::Warn);
+    assert!(app.is_headless());
+    let entity = {
+        let mut scene = app.get_active_scene().unwrap();
+        let mut world = scene.get_world().unwrap();
+        world
+            .spawn((TransformBuilder::new().build(), VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) }))
+            .id()
+    };
+
+    let ticks = 30;
+    app.run_headless(ticks);
+
+    assert_eq!(app.frame_count(), ticks);
+    assert_eq!(app.time().tick_count(), ticks);
+    let mut scene = app.get_active_scene().unwrap();
+    let world = scene.get_world().unwrap();
+    assert_eq!(world.resource::<Time>().frame_count(), ticks);
+    // VelocitySystem moved the entity once per tick
+    let x = world.get::<TransformComponent>(entity).unwrap().global_position.x;
+    let expected = ticks as f32 * app.fixed_timestep().step_seconds();
+    assert!((x - expected).abs() < 1e-4, "moved to {} instead of {}", x, expected);
+}
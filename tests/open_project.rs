@@ -0,0 +1,45 @@
+mod common;
+
+use ember_math::Vector3f;
+use log::LevelFilter;
+
+use ember::core::application::application_manager::Application;
+use ember::core::plugins::components::{TransformBuilder, VelocityComponent};
+use ember::core::project::project_template::create_project;
+use ember::core::scene::{DynamicScene, Scene, Staged, TypeRegistryResource};
+
+fn velocities(app: &Application) -> Vec<f32> {
+    let mut scene = app.get_active_scene().unwrap();
+    let mut world = scene.get_world().unwrap();
+    let mut query = world.query::<&VelocityComponent>();
+    query.iter(&world).map(|v| v.velocity.x).collect()
+}
+
+#[test]
+fn opening_a_project_spawns_its_saved_scene(){
+    let dir = common::temp_project_dir("open_project");
+    let project_path = dir.join("game");
+    create_project(project_path.to_str().unwrap(), "level").unwrap();
+
+    // save a scene with something in it over the empty one the project starts with
+    let mut saved = Scene::<Staged>::new();
+    {
+        let mut world = saved.get_world().unwrap();
+        world.spawn((
+            TransformBuilder::new().with_global_position(Vector3f::new(7.0, 0.0, 0.0)).build(),
+            VelocityComponent{ velocity: Vector3f::new(3.0, 0.0, 0.0) },
+        ));
+        let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+        DynamicScene::from_world(&world, &type_registry)
+            .write_to_file(project_path.join("scenes").join("level.ron").to_str().unwrap(), &type_registry)
+            .unwrap();
+    }
+
+    // goes through the scene manager's OpenProject message and the background load
+    let mut app = Application::create_headless_application(LevelFilter::Warn);
+    app.open_project(project_path.to_string_lossy().to_string(), None);
+    assert!(common::run_until(&mut app, |app| !velocities(app).is_empty()));
+    assert_eq!(velocities(&app), vec![3.0]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,60 @@
+use bevy_ecs::entity::Entity;
+use bevy_hierarchy::{BuildWorldChildren, Children, Parent};
+use ember_math::Vector3f;
+
+use ember::core::application::{ApplicationPlayState, ApplicationState};
+use ember::core::managers::SceneManager;
+use ember::core::plugins::components::{AppInterfaceFlag, VelocityComponent};
+use ember::core::scene::{Scene, Staged};
+
+fn velocity(x: f32) -> VelocityComponent {
+    VelocityComponent{ velocity: Vector3f::new(x, 0.0, 0.0) }
+}
+
+#[test]
+fn stopping_puts_back_the_scene_from_before_play(){
+    let mut staged = Scene::<Staged>::new();
+    let (parent, interface) = {
+        let mut world = staged.get_world().unwrap();
+        let parent = world.spawn(velocity(1.0)).id();
+        let child = world.spawn(velocity(2.0)).id();
+        // a piece of the editor hanging off a scene entity
+        let interface = world.spawn(AppInterfaceFlag).id();
+        world.entity_mut(parent).push_children(&[child, interface]);
+        (parent, interface)
+    };
+    let mut manager = SceneManager::new();
+    manager.set_staged_scene(staged);
+    manager.activate_staged_scene();
+
+    let mut play = ApplicationPlayState::create();
+    play.on_enter(&mut manager.get_active_scene().unwrap());
+    {
+        let mut scene = manager.get_active_scene().unwrap();
+        let mut world = scene.get_world().unwrap();
+        world.get_mut::<VelocityComponent>(parent).unwrap().velocity = Vector3f::new(9.0, 0.0, 0.0);
+        let spawned = world.spawn(velocity(7.0)).id();
+        world.entity_mut(parent).push_children(&[spawned]);
+    }
+    play.on_exit(&mut manager.get_active_scene().unwrap());
+
+    let mut scene = manager.get_active_scene().unwrap();
+    let mut world = scene.get_world().unwrap();
+    let mut velocities: Vec<f32> = world
+        .query::<&VelocityComponent>()
+        .iter(&world)
+        .map(|v| v.velocity.x)
+        .collect();
+    velocities.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(velocities, vec![1.0, 2.0]);
+
+    // the restored hierarchy is whole and nothing points at a despawned entity
+    let mut parents = world.query::<(Entity, &VelocityComponent, &Children)>();
+    let (restored_parent, _, children) = parents.iter(&world).find(|(_, v, _)| v.velocity.x == 1.0).unwrap();
+    let children: Vec<Entity> = children.iter().copied().collect();
+    assert_eq!(children.len(), 1);
+    assert_eq!(world.get::<VelocityComponent>(children[0]).unwrap().velocity.x, 2.0);
+    assert_eq!(world.get::<Parent>(children[0]).unwrap().get(), restored_parent);
+    assert!(world.get::<AppInterfaceFlag>(interface).is_some());
+    assert!(world.get::<Parent>(interface).is_none());
+}
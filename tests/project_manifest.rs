@@ -0,0 +1,105 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use ember::core::errors::EngineError;
+use ember::core::project::project_manifest::{ENGINE_VERSION, PROJECT_FILE};
+use ember::core::project::{CurrentProject, ProjectManifest};
+
+fn project_dir(name: &str) -> PathBuf {
+    let dir = common::temp_project_dir(name);
+    fs::create_dir_all(dir.join("scenes")).unwrap();
+    fs::create_dir_all(dir.join("assets")).unwrap();
+    fs::write(dir.join("scenes").join("default.ron"), "").unwrap();
+    fs::write(dir.join("scenes").join("level.ron"), "").unwrap();
+    dir
+}
+
+#[test]
+fn manifests_are_written_read_and_pick_the_opened_scene(){
+    let dir = project_dir("manifest");
+    let mut manifest = ProjectManifest::new("game");
+    manifest.add_scene("level");
+    manifest.default_scene = String::from("level.ron");
+    manifest.write(&dir).unwrap();
+
+    let read = ProjectManifest::read(&dir).unwrap();
+    assert_eq!(read, manifest);
+    assert_eq!(read.engine_version, ENGINE_VERSION);
+
+    let project = CurrentProject::open(dir.to_str().unwrap(), None).unwrap();
+    assert_eq!(project.scene_name, "level.ron");
+    let project = CurrentProject::open(dir.to_str().unwrap(), Some("default")).unwrap();
+    assert_eq!(project.scene_name, "default.ron");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_manifests_report_every_problem(){
+    let dir = project_dir("bad_manifest");
+    fs::write(dir.join(PROJECT_FILE), r#"(
+        name: "",
+        engine_version: "999.0.0",
+        default_scene: "missing.ron",
+        scenes: ["missing.ron"],
+        asset_dirs: ["../elsewhere"],
+        plugins: ["/usr/lib/libgone.so"],
+    )"#).unwrap();
+
+    let reason = match ProjectManifest::read(&dir) {
+        Err(EngineError::ProjectManifest{reason, ..}) => reason,
+        other => panic!("expected a manifest error, got {:?}", other),
+    };
+    for problem in ["name is empty", "newer than this one", "scene 'missing.ron'", "outside the project", "plugin '/usr/lib/libgone.so' is outside"] {
+        assert!(reason.contains(problem), "'{}' isn't in '{}'", problem, reason);
+    }
+
+    fs::write(dir.join(PROJECT_FILE), "(name: \"game\"").unwrap();
+    assert!(ProjectManifest::read(&dir).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn empty_project_files_get_a_manifest(){
+    let dir = project_dir("old_project");
+    fs::write(dir.join(PROJECT_FILE), "").unwrap();
+
+    let manifest = ProjectManifest::read(&dir).unwrap();
+    assert_eq!(manifest.default_scene, "default.ron");
+    assert_eq!(manifest.scenes, vec![String::from("default.ron"), String::from("level.ron")]);
+    assert!(!fs::read_to_string(dir.join(PROJECT_FILE)).unwrap().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scene_names_with_paths_are_rejected(){
+    let dir = project_dir("scene_names");
+    ProjectManifest::new("game").write(&dir).unwrap();
+    assert!(matches!(
+        CurrentProject::open(dir.to_str().unwrap(), Some("../outside")),
+        Err(EngineError::InvalidSceneName(_))
+    ));
+
+    let mut manifest = ProjectManifest::new("game");
+    manifest.scenes.push(String::from("../default.ron"));
+    let problems = manifest.validate(&dir).unwrap_err();
+    assert!(problems.iter().any(|p| p.contains("'../default.ron' isn't a scene name")), "{:?}", problems);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn registered_scenes_go_into_the_manifest(){
+    let dir = project_dir("register_scene");
+    let mut project = CurrentProject::new(dir.to_str().unwrap(), "default");
+    project.register_scene("level");
+    project.register_scene("level.ron");
+    assert_eq!(project.scene_name, "level.ron");
+    assert_eq!(project.manifest.scenes, vec![String::from("default.ron"), String::from("level.ron")]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
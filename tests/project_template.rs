@@ -0,0 +1,82 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ember::core::project::project_template::{
+    create_project,
+    crate_name,
+    plugin_type_name,
+    relative_path,
+    render_template,
+    EMBER_PATH_VAR,
+};
+use ember::core::project::ProjectManifest;
+use ember::core::scene::scene_format::parse_scene;
+use ember::core::scene::{MigrationRegistry, TypeRegistryResource};
+
+#[test]
+fn names_are_made_valid_for_rust(){
+    assert_eq!(crate_name("My Game"), "my_game");
+    assert_eq!(crate_name("3d-demo"), "game_3d_demo");
+    assert_eq!(plugin_type_name("My Game"), "MyGamePlugin");
+    assert_eq!(render_template("{{a}} {{b}} {{c}}", &[("a", "1"), ("b", "2")]), "1 2 {{c}}");
+}
+
+#[test]
+fn relative_paths_go_up_to_what_the_directories_share(){
+    assert_eq!(relative_path(Path::new("/a/b/game"), Path::new("/a/engine")), Some(PathBuf::from("../../engine")));
+    assert_eq!(relative_path(Path::new("/a/engine/games/x"), Path::new("/a/engine")), Some(PathBuf::from("../..")));
+    assert_eq!(relative_path(Path::new("/a"), Path::new("/a/engine")), Some(PathBuf::from("engine")));
+    assert_eq!(relative_path(Path::new("/a"), Path::new("relative")), None);
+}
+
+// the engine dependency in a project's Cargo.toml
+fn ember_path(project_dir: &Path) -> String {
+    let cargo_toml = fs::read_to_string(project_dir.join("Cargo.toml")).unwrap();
+    let line = cargo_toml.lines().find(|l| l.starts_with("ember = ")).unwrap();
+    let start = line.find("path = \"").unwrap() + "path = \"".len();
+    let end = start + line[start..].find('"').unwrap();
+    String::from(&line[start..end])
+}
+
+#[test]
+fn new_projects_are_ready_to_open_and_build(){
+    let dir = common::temp_project_dir("template").join("My Game");
+    let project_path = dir.to_string_lossy().to_string();
+    create_project(&project_path, "start").unwrap();
+    assert!(create_project(&project_path, "start").is_err());
+
+    let manifest = ProjectManifest::read(&dir).unwrap();
+    assert_eq!(manifest.name, "My Game");
+    assert_eq!(manifest.default_scene, "start.ron");
+    assert!(dir.join("assets").is_dir());
+
+    let scene_path = dir.join("scenes").join("start.ron").to_string_lossy().to_string();
+    let bytes = fs::read(&scene_path).unwrap();
+    assert!(!bytes.is_empty());
+    let registry = TypeRegistryResource::default().0;
+    parse_scene(&scene_path, &bytes, &registry, &MigrationRegistry::new()).unwrap();
+
+    let cargo_toml = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("name = \"my_game\""));
+    assert!(cargo_toml.contains("crate-type = [\"cdylib\"]"));
+    // relative, so the project doesn't only build on this machine
+    let engine = ember_path(&dir);
+    assert!(Path::new(&engine).is_relative(), "{} isn't relative", engine);
+    assert!(dir.join(&engine).join("Cargo.toml").is_file());
+    let lib_rs = fs::read_to_string(dir.join("src").join("lib.rs")).unwrap();
+    assert!(lib_rs.contains("declare_plugin!(MyGamePlugin, create);"));
+    assert!(!lib_rs.contains("{{"));
+    assert!(manifest.plugins[0].contains("my_game"));
+
+    // the only test here that creates projects, so setting the variable doesn't race another
+    std::env::set_var(EMBER_PATH_VAR, "../engine");
+    let other = dir.parent().unwrap().join("Other Game");
+    let created = create_project(&other.to_string_lossy(), "start");
+    std::env::remove_var(EMBER_PATH_VAR);
+    created.unwrap();
+    assert_eq!(ember_path(&other), "../engine");
+
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ember::core::project::RecentProjects;
+use ember::core::project::project_manifest::PROJECT_FILE;
+use ember::core::project::recent_projects::{opened_ago, MAX_RECENT_PROJECTS};
+
+fn make_project(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::create_dir_all(&path).unwrap();
+    fs::write(path.join(PROJECT_FILE), "").unwrap();
+    path.canonicalize().unwrap()
+}
+
+#[test]
+fn recent_projects_are_saved_most_recent_first_without_missing_ones(){
+    let dir = common::temp_project_dir("recent_projects");
+    let first = make_project(&dir, "first");
+    let second = make_project(&dir, "second");
+
+    let mut recent = RecentProjects::default();
+    recent.record(&first, "first");
+    recent.record(&second, "second");
+    // opening it again moves it back to the top instead of adding it twice
+    recent.record(&first, "first");
+    let paths: Vec<&PathBuf> = recent.projects.iter().map(|p| &p.path).collect();
+    assert_eq!(paths, vec![&first, &second]);
+    assert_eq!(recent.most_recent().unwrap().name, "first");
+    assert_eq!(opened_ago(recent.most_recent().unwrap().opened), "just now");
+
+    let file = dir.join("config").join("recent_projects.ron");
+    recent.write(&file).unwrap();
+    assert_eq!(RecentProjects::read(&file).unwrap(), recent);
+
+    // a deleted project is dropped when the list is read back
+    fs::remove_dir_all(&second).unwrap();
+    let read = RecentProjects::read(&file).unwrap();
+    assert_eq!(read.projects.len(), 1);
+    assert_eq!(read.projects[0].path, first);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn only_the_latest_projects_are_kept(){
+    let dir = common::temp_project_dir("recent_projects_cap");
+    let mut recent = RecentProjects::default();
+    for i in 0..MAX_RECENT_PROJECTS + 3 {
+        let name = format!("project_{}", i);
+        recent.record(&make_project(&dir, &name), &name);
+    }
+    assert_eq!(recent.projects.len(), MAX_RECENT_PROJECTS);
+    assert_eq!(recent.most_recent().unwrap().name, format!("project_{}", MAX_RECENT_PROJECTS + 2));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_projects_dont_take_up_places(){
+    let dir = common::temp_project_dir("recent_projects_gone");
+    let mut recent = RecentProjects::default();
+    let gone = make_project(&dir, "gone");
+    recent.record(&gone, "gone");
+    for i in 0..MAX_RECENT_PROJECTS - 1 {
+        let name = format!("project_{}", i);
+        recent.record(&make_project(&dir, &name), &name);
+    }
+    fs::remove_dir_all(&gone).unwrap();
+    recent.record(&make_project(&dir, "new"), "new");
+    assert_eq!(recent.projects.len(), MAX_RECENT_PROJECTS);
+    assert!(recent.projects.iter().all(|p| p.path != gone));
+    assert_eq!(recent.projects.last().unwrap().name, "project_0");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn loading_drops_missing_projects_from_the_file(){
+    let dir = common::temp_project_dir("recent_projects_load");
+    let kept = make_project(&dir, "kept");
+    let gone = make_project(&dir, "gone");
+    let mut recent = RecentProjects::default();
+    recent.record(&gone, "gone");
+    recent.record(&kept, "kept");
+
+    // the only test here that reads the user's config directory
+    std::env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+    recent.save().unwrap();
+    fs::remove_dir_all(&gone).unwrap();
+
+    assert_eq!(RecentProjects::load().projects.len(), 1);
+    let file = dir.join("config").join("ember").join("recent_projects.ron");
+    let saved: RecentProjects = ron::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    assert_eq!(saved.projects.len(), 1);
+    assert_eq!(saved.projects[0].path, kept);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
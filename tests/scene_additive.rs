@@ -0,0 +1,36 @@
+mod common;
+
+use ember_math::Vector3f;
+
+use ember::core::managers::SceneManager;
+use ember::core::plugins::components::{SceneHandle, VelocityComponent};
+use ember::core::scene::{DynamicScene, Scene, Staged, TypeRegistryResource};
+
+#[test]
+fn failed_additive_loads_dont_use_up_handles(){
+    let dir = common::temp_project_dir("scene_additive");
+    let scene_path = dir.join("props.ron").to_string_lossy().to_string();
+    let broken_path = dir.join("broken.ron").to_string_lossy().to_string();
+    {
+        let mut saved = Scene::<Staged>::new();
+        let mut world = saved.get_world().unwrap();
+        world.spawn(VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) });
+        let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+        DynamicScene::from_world(&world, &type_registry)
+            .write_to_file(&scene_path, &type_registry)
+            .unwrap();
+    }
+    std::fs::write(&broken_path, "(entities: {").unwrap();
+
+    let mut manager = SceneManager::new();
+    manager.set_staged_scene(Scene::<Staged>::new());
+    manager.activate_staged_scene();
+
+    assert!(manager.load_scene_additive(&broken_path).is_err());
+    assert!(manager.load_scene_additive(&dir.join("missing.ron").to_string_lossy()).is_err());
+    assert_eq!(manager.load_scene_additive(&scene_path).unwrap(), SceneHandle(1));
+    assert!(manager.load_scene_additive(&broken_path).is_err());
+    assert_eq!(manager.load_scene_additive(&scene_path).unwrap(), SceneHandle(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
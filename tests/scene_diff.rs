@@ -0,0 +1,142 @@
+use bevy_ecs::entity::{Entity, EntityMap};
+use bevy_ecs::prelude::Resource;
+use bevy_ecs::reflect::ReflectResource;
+use bevy_ecs::world::World;
+use bevy_reflect::Reflect;
+use ember_math::Vector3f;
+
+use ember::core::plugins::components::{TransformBuilder, TransformComponent, VelocityComponent};
+use ember::core::scene::scene_diff::{world_entity_map, SceneChange};
+use ember::core::scene::{DynamicScene, Scene, SceneDiff, Staged, TypeRegistryResource};
+
+const TRANSFORM: &str = "ember::core::plugins::components::transform_component::TransformComponent";
+
+#[derive(Resource, Reflect, Default, Debug, PartialEq)]
+#[reflect(Resource)]
+struct Gravity{
+    y: f32,
+}
+
+fn spawn_scene(world: &mut World) -> Vec<Entity> {
+    vec![
+        world.spawn(TransformBuilder::new().build()).id(),
+        world.spawn((TransformBuilder::new().build(), VelocityComponent::default())).id(),
+        world.spawn(VelocityComponent::default()).id(),
+    ]
+}
+
+// changes one field, adds an entity, removes a component and removes an entity
+fn edit(world: &mut World, entities: &[Entity]){
+    world.get_mut::<TransformComponent>(entities[0]).unwrap().global_position.y = 4.0;
+    world.spawn(VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) });
+    world.entity_mut(entities[1]).remove::<VelocityComponent>();
+    world.despawn(entities[2]);
+}
+
+#[test]
+fn diffs_apply_as_patches_and_invert(){
+    let mut edited = Scene::<Staged>::new();
+    let mut edited_world = edited.get_world().unwrap();
+    let registry = edited_world.resource::<TypeRegistryResource>().0.clone();
+    let entities = spawn_scene(&mut edited_world);
+    let original = DynamicScene::from_world(&edited_world, &registry);
+    edit(&mut edited_world, &entities);
+
+    let diff = SceneDiff::against_world(&original, &edited_world, &registry).unwrap();
+    assert!(diff.changes.contains(&SceneChange::ChangeField{
+        entity: entities[0].index(),
+        component: String::from(TRANSFORM),
+        field: String::from("global_position.y"),
+        old: String::from("0.0"),
+        new: String::from("4.0"),
+    }));
+    // only the field that changed
+    assert_eq!(diff.changes.iter().filter(|c| matches!(c, SceneChange::ChangeField{..})).count(), 1);
+    let diff = SceneDiff::from_ron(&diff.to_ron().unwrap()).unwrap();
+
+    let mut patched = Scene::<Staged>::new();
+    let mut world = patched.get_world().unwrap();
+    original.write_to_world(&mut world, &mut EntityMap::default()).unwrap();
+    let mut entity_map = world_entity_map(&world);
+    diff.apply(&mut world, &mut entity_map, &registry).unwrap();
+    assert!(SceneDiff::between(
+        &DynamicScene::from_world(&world, &registry),
+        &DynamicScene::from_world(&edited_world, &registry),
+        &registry,
+    ).unwrap().is_empty());
+
+    diff.inverse().apply(&mut world, &mut entity_map, &registry).unwrap();
+    assert!(SceneDiff::against_world(&original, &world, &registry).unwrap().is_empty());
+}
+
+#[test]
+fn diffs_that_cant_be_applied_leave_the_world_alone(){
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    let registry = world.resource::<TypeRegistryResource>().0.clone();
+    let entities = spawn_scene(&mut world);
+    let count = world.entities().len();
+
+    // fine up to the last change, which names a field the transform doesn't have
+    let diff = SceneDiff{
+        changes: vec![
+            SceneChange::AddEntity{ entity: 100 },
+            SceneChange::ChangeField{
+                entity: entities[0].index(),
+                component: String::from(TRANSFORM),
+                field: String::from("global_position.y"),
+                old: String::from("0.0"),
+                new: String::from("4.0"),
+            },
+            SceneChange::RemoveEntity{ entity: entities[2].index() },
+            SceneChange::ChangeField{
+                entity: entities[1].index(),
+                component: String::from(TRANSFORM),
+                field: String::from("global_position.w"),
+                old: String::from("0.0"),
+                new: String::from("1.0"),
+            },
+        ],
+    };
+    let mut entity_map = world_entity_map(&world);
+    assert!(diff.apply(&mut world, &mut entity_map, &registry).is_err());
+
+    assert_eq!(world.entities().len(), count);
+    assert!(world.get_entity(entities[2]).is_some());
+    assert_eq!(world.get::<TransformComponent>(entities[0]).unwrap().global_position.y, 0.0);
+    assert!(entity_map.get(Entity::from_raw(100)).is_err());
+}
+
+#[test]
+fn resources_are_diffed_and_patched(){
+    let mut edited = Scene::<Staged>::new();
+    let mut edited_world = edited.get_world().unwrap();
+    let registry = edited_world.resource::<TypeRegistryResource>().0.clone();
+    registry.write().register::<Gravity>();
+    spawn_scene(&mut edited_world);
+    edited_world.insert_resource(Gravity{ y: -9.8 });
+    let original = DynamicScene::from_world(&edited_world, &registry);
+    edited_world.resource_mut::<Gravity>().y = -1.6;
+
+    let diff = SceneDiff::against_world(&original, &edited_world, &registry).unwrap();
+    assert_eq!(diff.changes, vec![SceneChange::ChangeResourceField{
+        resource: String::from(std::any::type_name::<Gravity>()),
+        field: String::from("y"),
+        old: String::from("-9.8"),
+        new: String::from("-1.6"),
+    }]);
+
+    let mut patched = Scene::<Staged>::new();
+    let mut world = patched.get_world().unwrap();
+    original.write_to_world_with(&mut world, &mut EntityMap::default(), &registry).unwrap();
+    let mut entity_map = world_entity_map(&world);
+    diff.apply(&mut world, &mut entity_map, &registry).unwrap();
+    assert_eq!(*world.resource::<Gravity>(), Gravity{ y: -1.6 });
+
+    // a scene without the resource removes it
+    world.remove_resource::<Gravity>();
+    let diff = SceneDiff::between(&original, &DynamicScene::from_world(&world, &registry), &registry).unwrap();
+    assert!(matches!(&diff.changes[..], [SceneChange::RemoveResource{..}]));
+    diff.inverse().apply(&mut world, &mut entity_map, &registry).unwrap();
+    assert_eq!(*world.resource::<Gravity>(), Gravity{ y: -9.8 });
+}
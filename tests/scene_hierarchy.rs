@@ -0,0 +1,91 @@
+use bevy_ecs::entity::{Entity, EntityMap};
+use bevy_ecs::world::World;
+use bevy_hierarchy::{BuildWorldChildren, Children, Parent};
+use serde::de::DeserializeSeed;
+
+use ember::core::plugins::components::TransformBuilder;
+use ember::core::scene::{
+    DynamicScene,
+    Scene,
+    SceneDeserializer,
+    Staged,
+    TypeRegistryResource,
+};
+
+// saves to ron and reads it back so the components are the dynamic ones a scene file gives you
+fn through_ron(world: &World) -> DynamicScene {
+    let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+    let ron_str = DynamicScene::from_world(world, &type_registry)
+        .serialize_ron(&type_registry)
+        .expect("couldn't serialize scene");
+    let mut deserializer = ron::de::Deserializer::from_str(&ron_str).expect("invalid ron");
+    SceneDeserializer{ type_registry: &*type_registry.read(), migrations: None }
+        .deserialize(&mut deserializer)
+        .expect("couldn't deserialize scene")
+}
+
+#[test]
+fn loading_a_hierarchy_twice_keeps_the_copies_separate(){
+    let mut saved = Scene::<Staged>::new();
+    let scene = {
+        let mut world = saved.get_world().unwrap();
+        let parent = world.spawn(TransformBuilder::new().build()).id();
+        let child = world.spawn(TransformBuilder::new().build()).id();
+        world.entity_mut(parent).push_children(&[child]);
+        through_ron(&world)
+    };
+    let (saved_parent, saved_child) = (Entity::from_raw(0), Entity::from_raw(1));
+
+    let mut target = Scene::<Staged>::new();
+    let mut world = target.get_world().unwrap();
+    // something already in the world so the loaded ids don't line up with the saved ones
+    world.spawn(TransformBuilder::new().build());
+
+    let mut copies = Vec::new();
+    for _ in 0..2 {
+        let mut entity_map = EntityMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).expect("couldn't spawn scene");
+        copies.push((
+            entity_map.get(saved_parent).unwrap(),
+            entity_map.get(saved_child).unwrap(),
+        ));
+    }
+    assert_ne!(copies[0], copies[1]);
+
+    for (parent, child) in copies {
+        assert_eq!(world.get::<Parent>(child).unwrap().get(), parent);
+        let children: Vec<Entity> = world.get::<Children>(parent).unwrap().iter().copied().collect();
+        assert_eq!(children, vec![child]);
+    }
+}
+
+#[test]
+fn references_to_reused_entities_are_mapped(){
+    let mut saved = Scene::<Staged>::new();
+    let scene = {
+        let mut world = saved.get_world().unwrap();
+        // freed so the hierarchy below reuses their indices with a newer generation
+        let freed = [world.spawn_empty().id(), world.spawn_empty().id()];
+        for entity in freed {
+            world.despawn(entity);
+        }
+        let parent = world.spawn(TransformBuilder::new().build()).id();
+        let child = world.spawn(TransformBuilder::new().build()).id();
+        assert!(parent.generation() > 0 && child.generation() > 0);
+        world.entity_mut(parent).push_children(&[child]);
+        through_ron(&world)
+    };
+
+    let mut target = Scene::<Staged>::new();
+    let mut world = target.get_world().unwrap();
+    let mut entity_map = EntityMap::default();
+    scene.write_to_world(&mut world, &mut entity_map).expect("couldn't spawn scene");
+
+    let mut query = world.query::<(Entity, &Parent)>();
+    let parents: Vec<(Entity, Entity)> = query.iter(&world).map(|(e, p)| (e, p.get())).collect();
+    assert_eq!(parents.len(), 1);
+    let (child, parent) = parents[0];
+    assert!(entity_map.values().any(|e| e == parent));
+    let children: Vec<Entity> = world.get::<Children>(parent).unwrap().iter().copied().collect();
+    assert_eq!(children, vec![child]);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use ember::core::plugins::components::{GeometryComponent, GeometryType, MeshSource};
+use ember::core::rendering::geometries::mesh::{load_mesh_file, parse_obj};
+use ember::core::rendering::geometries::{MeshData, Vertex};
+use ember::core::scene::{Scene, Staged};
+
+const QUAD_OBJ: &str = "
+# a unit quad
+v 0 0 0
+v 1 0 0
+v 1 0 1
+v 0 0 1
+vn 0 1 0
+f 1//1 2//1 3//1 -1//1
+";
+
+#[test]
+fn obj_faces_are_split_into_triangles(){
+    let mesh = parse_obj(QUAD_OBJ).unwrap();
+    assert_eq!(mesh.positions.len(), 4);
+    assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert!(parse_obj("v 0 0\n").is_err());
+    assert!(parse_obj("v 0 0 0\nf 1 2\n").is_err());
+}
+
+#[test]
+fn mesh_files_are_read_and_checked(){
+    let dir = common::temp_project_dir("mesh");
+    let obj_path = dir.join("quad.obj").to_string_lossy().to_string();
+    std::fs::write(&obj_path, QUAD_OBJ).unwrap();
+    assert_eq!(load_mesh_file(&obj_path).unwrap().indices.len(), 6);
+
+    let ron_path = dir.join("broken.ron").to_string_lossy().to_string();
+    let broken = MeshData{ positions: vec![[0.0, 0.0, 0.0]], indices: vec![0, 1, 0] };
+    std::fs::write(&ron_path, ron::to_string(&broken).unwrap()).unwrap();
+    assert!(load_mesh_file(&ron_path).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn inline_meshes_are_saved_with_the_scene(){
+    let vertices = vec![Vertex::new(0.0, 0.0, 0.0), Vertex::new(1.0, 0.0, 0.0), Vertex::new(0.0, 1.0, 0.0)];
+    let mut saved = Scene::<Staged>::new();
+    let ron_str = {
+        let mut world = saved.get_world().unwrap();
+        world.spawn(GeometryComponent::from_mesh(vertices.clone(), vec![0, 1, 2]));
+        world.spawn(GeometryComponent::from_mesh_file("meshes/rock.obj"));
+        common::save_ron(&world)
+    };
+
+    let mut loaded = Scene::<Staged>::new();
+    let mut world = loaded.get_world().unwrap();
+    common::load_ron(&mut world, &ron_str);
+
+    let mut query = world.query::<&GeometryComponent>();
+    let types: Vec<GeometryType> = query.iter(&world).map(|g| g.geometry_type.clone()).collect();
+    assert!(types.contains(&GeometryType::Mesh(MeshSource::Inline(MeshData::new(&vertices, &[0, 1, 2])))));
+    assert!(types.contains(&GeometryType::Mesh(MeshSource::File(String::from("meshes/rock.obj")))));
+}
@@ -0,0 +1,138 @@
+use bevy_ecs::entity::EntityMap;
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_ecs::world::World;
+use bevy_reflect::Reflect;
+use ember_math::Vector3f;
+
+use ember::core::plugins::components::{CameraComponent, VelocityComponent};
+use ember::core::scene::migration::set_schema_version;
+use ember::core::scene::scene_format::{parse_scene, serialize_binary};
+use ember::core::scene::{
+    DynamicScene,
+    MigrationRegistry,
+    Scene,
+    Staged,
+    TypeRegistryResource,
+};
+
+const CAMERA: &str = "ember::core::plugins::components::camera_component::CameraComponent";
+const VELOCITY: &str = "ember::core::plugins::components::velocity_component::VelocityComponent";
+
+// components only the engine that saved the binary scene below knew about
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct PluginComponent{
+    value: u32,
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct RemovedComponent{
+    value: String,
+}
+
+// laid out like VelocityComponent, which it was renamed to
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct OldVelocity{
+    velocity: Vector3f,
+}
+
+// a scene from before versioning. the camera's fov was called field_of_view and near didn't
+// exist, and one component was removed while another was never registered
+const OLD_SCENE: &str = r#"(
+  entities: {
+    0: (
+      components: {
+        "ember::core::plugins::components::camera_component::CameraComponent": (
+          field_of_view: 1.0,
+          far: 100.0,
+          aspect: 2.0,
+          orbit_speed: 0.5,
+        ),
+        "ember::core::plugins::components::old_component::OldComponent": (
+          value: 3,
+        ),
+      },
+    ),
+    1: (
+      components: {
+        "some_plugin::NeverRegistered": (),
+        "ember::core::plugins::components::velocity_component::VelocityComponent": (
+          velocity: (
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+          ),
+        ),
+      },
+    ),
+  },
+)"#;
+
+#[test]
+fn old_scenes_are_migrated_and_unknown_components_skipped(){
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+    set_schema_version::<CameraComponent>(&mut type_registry.write(), 1);
+
+    let mut migrations = MigrationRegistry::new();
+    migrations
+        .rename_field(CAMERA, 1, "field_of_view", "fov")
+        .default_field(CAMERA, 1, "near", 0.5f32)
+        .remove_type("ember::core::plugins::components::old_component::OldComponent");
+
+    let loaded = parse_scene("old.ron", OLD_SCENE.as_bytes(), &type_registry, &migrations)
+        .expect("old scene should load");
+    let mut entity_map = EntityMap::default();
+    loaded.write_to_world(&mut world, &mut entity_map).unwrap();
+
+    let camera = camera(&mut world);
+    assert_eq!(camera.fov, 1.0);
+    assert_eq!(camera.near, 0.5);
+    assert_eq!(camera.far, 100.0);
+    assert_eq!(world.query::<&VelocityComponent>().iter(&world).count(), 1);
+}
+
+fn camera(world: &mut World) -> CameraComponent {
+    world.query::<&CameraComponent>().single(world).clone()
+}
+
+#[test]
+fn binary_scenes_skip_unknown_and_removed_components(){
+    let mut saved = Scene::<Staged>::new();
+    let bytes = {
+        let mut world = saved.get_world().unwrap();
+        let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+        {
+            let mut registry = type_registry.write();
+            registry.register::<PluginComponent>();
+            registry.register::<RemovedComponent>();
+            registry.register::<OldVelocity>();
+        }
+        world.spawn((CameraComponent::default(), PluginComponent{ value: 7 }));
+        world.spawn((RemovedComponent{ value: String::from("gone") }, OldVelocity{ velocity: Vector3f::new(2.0, 0.0, 0.0) }));
+        let scene = DynamicScene::from_world(&world, &type_registry);
+        serialize_binary(&scene, &type_registry).unwrap()
+    };
+
+    // a newer engine without the plugin, where the camera gained a schema version
+    let type_registry = TypeRegistryResource::default().0;
+    set_schema_version::<CameraComponent>(&mut type_registry.write(), 1);
+    let mut migrations = MigrationRegistry::new();
+    migrations
+        .rename_field(CAMERA, 1, "field_of_view", "fov")
+        .rename_type(std::any::type_name::<OldVelocity>(), VELOCITY)
+        .remove_type(std::any::type_name::<RemovedComponent>());
+
+    let loaded = parse_scene("level.scn", &bytes, &type_registry, &migrations)
+        .expect("binary scene should load without the components it can't read");
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    loaded.write_to_world(&mut world, &mut EntityMap::default()).unwrap();
+
+    assert_eq!(camera(&mut world).fov, CameraComponent::default().fov);
+    let velocities: Vec<f32> = world.query::<&VelocityComponent>().iter(&world).map(|v| v.velocity.x).collect();
+    assert_eq!(velocities, vec![2.0]);
+}
@@ -0,0 +1,12 @@
+use ember::core::errors::EngineError;
+use ember::core::project::check_scene_name;
+
+#[test]
+fn scene_names_with_paths_are_rejected(){
+    for name in ["level", "level.ron", "level two.scn"] {
+        assert!(check_scene_name(name).is_ok(), "'{}' was rejected", name);
+    }
+    for name in ["", "..", "../../x", "/tmp/x", "scenes/x", "..\\x"] {
+        assert!(matches!(check_scene_name(name), Err(EngineError::InvalidSceneName(_))), "'{}' was allowed", name);
+    }
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use bevy_ecs::entity::Entity;
+use bevy_ecs::event::Events;
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_ecs::schedule::{Stage, SystemStage};
+use bevy_ecs::world::World;
+use bevy_hierarchy::{BuildWorldChildren, Children};
+use bevy_reflect::Reflect;
+use ember_math::Vector3f;
+
+use ember::core::errors::EngineError;
+use ember::core::events::error_events::ErrorEvent;
+use ember::core::events::prefab_events::SavePrefabEvent;
+use ember::core::plugins::components::{
+    PrefabInstanceComponent,
+    TransformBuilder,
+    TransformComponent,
+    VelocityComponent,
+};
+use ember::core::project::CurrentProject;
+use ember::core::scene::prefab::{instantiate_prefab, prefab_path, record_override, save_prefab, PREFAB_DIR};
+use ember::core::scene::{Scene, Staged, TypeRegistryResource};
+use ember::core::systems::PrefabSystem;
+
+const VELOCITY: &str = "ember::core::plugins::components::velocity_component::VelocityComponent";
+
+// points at another entity, like a target to follow
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Target{
+    entity: Entity,
+}
+
+impl Default for Target{
+    fn default() -> Self {
+        Target{ entity: Entity::from_raw(u32::MAX) }
+    }
+}
+
+fn child_of(world: &World, instance: Entity) -> Entity {
+    world.get::<Children>(instance).unwrap()[0]
+}
+
+fn velocity(world: &World, entity: Entity) -> Vector3f {
+    world.get::<VelocityComponent>(entity).unwrap().velocity.clone()
+}
+
+fn at(x: f32) -> TransformComponent {
+    TransformBuilder::new().with_global_position(Vector3f::new(x, 0.0, 0.0)).build()
+}
+
+#[test]
+fn prefab_changes_reach_instances_and_keep_overrides(){
+    let dir = common::temp_project_dir("prefab");
+    std::fs::create_dir_all(dir.join("scenes")).unwrap();
+
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    world.insert_resource(CurrentProject::new(dir.to_str().unwrap(), "default"));
+
+    let root = world.spawn(TransformBuilder::new().build()).id();
+    let child = world.spawn(VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) }).id();
+    world.entity_mut(root).push_children(&[child]);
+    let prefab = prefab_path("crate");
+    save_prefab(&mut world, root, &prefab).expect("couldn't save prefab");
+
+    let first = instantiate_prefab(&mut world, &prefab, at(5.0)).expect("couldn't spawn prefab");
+    let second = instantiate_prefab(&mut world, &prefab, at(-5.0)).expect("couldn't spawn prefab");
+    assert_eq!(world.get::<TransformComponent>(first).unwrap().global_position.x, 5.0);
+    assert_eq!(velocity(&world, child_of(&world, second)).x, 1.0);
+
+    // the second instance's child goes its own way
+    let second_child = child_of(&world, second);
+    world.get_mut::<VelocityComponent>(second_child).unwrap().velocity = Vector3f::new(0.0, 0.0, 9.0);
+    record_override(&mut world, second_child, VELOCITY, "velocity").unwrap();
+
+    // editing one instance and saving it over the prefab updates the others
+    let first_child = child_of(&world, first);
+    world.get_mut::<VelocityComponent>(first_child).unwrap().velocity = Vector3f::new(0.0, 2.0, 0.0);
+    save_prefab(&mut world, first, &prefab).expect("couldn't save prefab");
+
+    assert_eq!(velocity(&world, child_of(&world, first)).y, 2.0);
+    assert_eq!(velocity(&world, child_of(&world, second)).z, 9.0);
+    assert_eq!(world.get::<TransformComponent>(second).unwrap().global_position.x, -5.0);
+    // no entities were duplicated along the way
+    assert_eq!(world.query::<&PrefabInstanceComponent>().iter(&world).count(), 2);
+    assert_eq!(world.query::<&VelocityComponent>().iter(&world).count(), 3);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn references_outside_the_prefab_are_left_out(){
+    let dir = common::temp_project_dir("prefab_outside");
+    std::fs::create_dir_all(dir.join("scenes")).unwrap();
+
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    world.insert_resource(CurrentProject::new(dir.to_str().unwrap(), "default"));
+    world.resource::<TypeRegistryResource>().0.write().register::<Target>();
+
+    let outside = world.spawn(TransformBuilder::new().build()).id();
+    let root = world.spawn(TransformBuilder::new().build()).id();
+    let inside = world.spawn(Target{ entity: root }).id();
+    let escaping = world.spawn((
+        Target{ entity: outside },
+        VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) },
+    )).id();
+    world.entity_mut(root).push_children(&[inside, escaping]);
+    let prefab = prefab_path("follower");
+    save_prefab(&mut world, root, &prefab).expect("couldn't save prefab");
+
+    let instance = instantiate_prefab(&mut world, &prefab, at(0.0)).expect("couldn't spawn prefab");
+    let children: Vec<Entity> = world.get::<Children>(instance).unwrap().iter().copied().collect();
+    let targets: Vec<Entity> = children.iter()
+        .filter_map(|child| world.get::<Target>(*child).map(|t| t.entity))
+        .collect();
+    // the reference inside the prefab points at the new instance, the one outside is gone
+    assert_eq!(targets, vec![instance]);
+    // the rest of the entity holding it is still saved
+    assert!(children.iter().any(|child| world.get::<VelocityComponent>(*child).is_some()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn prefab_names_with_paths_are_rejected(){
+    let base = common::temp_project_dir("prefab_names");
+    let dir = base.join("game");
+    std::fs::create_dir_all(dir.join("scenes")).unwrap();
+
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    world.insert_resource(CurrentProject::new(dir.to_str().unwrap(), "default"));
+    let root = world.spawn(TransformBuilder::new().build()).id();
+    world.send_event(SavePrefabEvent{ entity: root, name: String::from("../../escaped") });
+    SystemStage::single_threaded().with_system(PrefabSystem).run(&mut world);
+
+    let events = world.resource::<Events<ErrorEvent>>();
+    let mut reader = events.get_reader();
+    assert!(matches!(reader.iter(events).next(), Some(ErrorEvent(EngineError::InvalidSceneName(_)))));
+    // nothing was written, in the project or next to it
+    assert!(!dir.join(PREFAB_DIR).exists());
+    assert!(!base.join("escaped.ron").exists());
+
+    std::fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn components_taken_out_of_a_prefab_leave_its_instances(){
+    let dir = common::temp_project_dir("prefab_removed");
+    std::fs::create_dir_all(dir.join("scenes")).unwrap();
+
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    world.insert_resource(CurrentProject::new(dir.to_str().unwrap(), "default"));
+    world.resource::<TypeRegistryResource>().0.write().register::<Target>();
+
+    let root = world.spawn(TransformBuilder::new().build()).id();
+    let child = world.spawn((
+        Target{ entity: root },
+        VelocityComponent{ velocity: Vector3f::new(1.0, 0.0, 0.0) },
+    )).id();
+    world.entity_mut(root).push_children(&[child]);
+    let prefab = prefab_path("mover");
+    save_prefab(&mut world, root, &prefab).expect("couldn't save prefab");
+
+    let edited = instantiate_prefab(&mut world, &prefab, at(0.0)).expect("couldn't spawn prefab");
+    let plain = instantiate_prefab(&mut world, &prefab, at(1.0)).expect("couldn't spawn prefab");
+    let overriding = instantiate_prefab(&mut world, &prefab, at(2.0)).expect("couldn't spawn prefab");
+    let overriding_child = child_of(&world, overriding);
+    world.get_mut::<VelocityComponent>(overriding_child).unwrap().velocity = Vector3f::new(0.0, 3.0, 0.0);
+    record_override(&mut world, overriding_child, VELOCITY, "velocity").unwrap();
+
+    let edited_child = child_of(&world, edited);
+    world.entity_mut(edited_child).remove::<VelocityComponent>();
+    save_prefab(&mut world, edited, &prefab).expect("couldn't save prefab");
+
+    assert!(world.get::<VelocityComponent>(child_of(&world, plain)).is_none());
+    assert_eq!(velocity(&world, child_of(&world, overriding)).y, 3.0);
+    // what's still in the prefab stays on every instance
+    for instance in [edited, plain, overriding] {
+        assert_eq!(world.get::<Target>(child_of(&world, instance)).unwrap().entity, instance);
+        assert!(world.get::<TransformComponent>(instance).is_some());
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use bevy_ecs::world::World;
+use bevy_hierarchy::{BuildWorldChildren, Parent};
+use ember_math::Vector3f;
+
+use ember::core::managers::SceneManager;
+use ember::core::plugins::components::{AppInterfaceFlag, CameraComponent, VelocityComponent};
+use ember::core::scene::{DynamicScene, Scene, Staged, TypeRegistryResource};
+
+fn camera(fov: f32) -> CameraComponent {
+    CameraComponent{ fov, ..Default::default() }
+}
+
+fn velocity(x: f32) -> VelocityComponent {
+    VelocityComponent{ velocity: Vector3f::new(x, 0.0, 0.0) }
+}
+
+// the file as a designer left it, with a camera of its own
+fn save_scene(scene_path: &str){
+    let mut saved = Scene::<Staged>::new();
+    let mut world = saved.get_world().unwrap();
+    world.spawn(camera(2.0));
+    world.spawn(velocity(5.0));
+    let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+    DynamicScene::from_world(&world, &type_registry)
+        .write_to_file(scene_path, &type_registry)
+        .unwrap();
+}
+
+fn activate(spawn: impl FnOnce(&mut World)) -> SceneManager {
+    let mut staged = Scene::<Staged>::new();
+    spawn(&mut staged.get_world().unwrap());
+    let mut manager = SceneManager::new();
+    manager.set_staged_scene(staged);
+    manager.activate_staged_scene();
+    manager
+}
+
+fn velocities(manager: &SceneManager) -> Vec<f32> {
+    let mut scene = manager.get_active_scene().unwrap();
+    let mut world = scene.get_world().unwrap();
+    let mut query = world.query::<&VelocityComponent>();
+    query.iter(&world).map(|v| v.velocity.x).collect()
+}
+
+fn camera_fovs(manager: &SceneManager) -> Vec<f32> {
+    let mut scene = manager.get_active_scene().unwrap();
+    let mut world = scene.get_world().unwrap();
+    let mut query = world.query::<&CameraComponent>();
+    query.iter(&world).map(|c| c.fov).collect()
+}
+
+#[test]
+fn reloading_keeps_the_interface_and_camera_and_survives_bad_files(){
+    let dir = common::temp_project_dir("scene_reload");
+    let scene_path = dir.join("level.ron").to_string_lossy().to_string();
+    save_scene(&scene_path);
+
+    // the editor's world, with its interface and the camera being looked through
+    let mut interface = None;
+    let mut manager = activate(|world| {
+        world.spawn(camera(1.0));
+        world.spawn(velocity(1.0));
+        interface = Some(world.spawn(AppInterfaceFlag).id());
+    });
+    let interface = interface.unwrap();
+
+    assert!(manager.reload_active_scene(&scene_path).unwrap());
+    assert_eq!(velocities(&manager), vec![5.0]);
+    // cameras are left out of reloads on purpose, the file's camera doesn't replace the view
+    assert_eq!(camera_fovs(&manager), vec![1.0]);
+    {
+        let mut scene = manager.get_active_scene().unwrap();
+        let world = scene.get_world().unwrap();
+        assert!(world.get::<AppInterfaceFlag>(interface).is_some());
+    }
+
+    // half written
+    std::fs::write(&scene_path, "(entities: {").unwrap();
+    let count = {
+        let mut scene = manager.get_active_scene().unwrap();
+        let world = scene.get_world().unwrap();
+        world.entities().len()
+    };
+    assert!(manager.reload_active_scene(&scene_path).is_err());
+    assert_eq!(velocities(&manager), vec![5.0]);
+    assert_eq!(camera_fovs(&manager), vec![1.0]);
+    let mut scene = manager.get_active_scene().unwrap();
+    let world = scene.get_world().unwrap();
+    assert_eq!(world.entities().len(), count);
+    assert!(world.get::<AppInterfaceFlag>(interface).is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reloading_without_a_camera_uses_the_files(){
+    let dir = common::temp_project_dir("scene_reload_camera");
+    let scene_path = dir.join("level.ron").to_string_lossy().to_string();
+    save_scene(&scene_path);
+
+    let mut manager = activate(|world| {
+        world.spawn(velocity(1.0));
+    });
+    assert!(manager.reload_active_scene(&scene_path).unwrap());
+    assert_eq!(velocities(&manager), vec![5.0]);
+    assert_eq!(camera_fovs(&manager), vec![2.0]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reloading_replaces_whole_hierarchies_and_keeps_what_was_under_them(){
+    let dir = common::temp_project_dir("scene_reload_hierarchy");
+    let scene_path = dir.join("level.ron").to_string_lossy().to_string();
+    save_scene(&scene_path);
+
+    // a camera and a piece of the interface hanging off scene entities
+    let mut kept = Vec::new();
+    let mut manager = activate(|world| {
+        let parent = world.spawn(velocity(1.0)).id();
+        let child = world.spawn(velocity(2.0)).id();
+        let view = world.spawn(camera(1.0)).id();
+        let interface = world.spawn(AppInterfaceFlag).id();
+        world.entity_mut(parent).push_children(&[child, interface]);
+        world.entity_mut(child).push_children(&[view]);
+        kept = vec![view, interface];
+    });
+
+    assert!(manager.reload_active_scene(&scene_path).unwrap());
+    assert_eq!(velocities(&manager), vec![5.0]);
+    assert_eq!(camera_fovs(&manager), vec![1.0]);
+    let mut scene = manager.get_active_scene().unwrap();
+    let world = scene.get_world().unwrap();
+    for entity in kept {
+        assert!(world.get_entity(entity).is_some());
+        assert!(world.get::<Parent>(entity).is_none());
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
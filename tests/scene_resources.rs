@@ -0,0 +1,62 @@
+mod common;
+
+use bevy_ecs::prelude::Resource;
+use bevy_ecs::reflect::ReflectResource;
+use bevy_reflect::Reflect;
+
+use ember::core::scene::scene_resources::{save_resource_with_scene, skip_resource_in_scene};
+use ember::core::scene::{Scene, Staged, TypeRegistryResource};
+
+#[derive(Resource, Reflect, Default, Debug, PartialEq)]
+#[reflect(Resource)]
+struct ClearColor{
+    r: f32,
+    g: f32,
+    b: f32,
+}
+
+// saved without #[reflect(Resource)]
+#[derive(Resource, Reflect, Default, Debug, PartialEq)]
+struct Gravity{
+    y: f32,
+}
+
+// reflected as a resource but kept out of scenes
+#[derive(Resource, Reflect, Default, Debug, PartialEq)]
+#[reflect(Resource)]
+struct EditorGrid{
+    spacing: f32,
+}
+
+fn register(scene: &mut Scene<Staged>){
+    let world = scene.get_world().unwrap();
+    let mut registry = world.resource::<TypeRegistryResource>().0.write();
+    registry.register::<ClearColor>();
+    registry.register::<EditorGrid>();
+    save_resource_with_scene::<Gravity>(&mut registry);
+    skip_resource_in_scene::<EditorGrid>(&mut registry);
+}
+
+#[test]
+fn opted_in_resources_are_saved_and_restored(){
+    let mut saved = Scene::<Staged>::new();
+    register(&mut saved);
+    let ron_str = {
+        let mut world = saved.get_world().unwrap();
+        world.insert_resource(ClearColor{ r: 0.1, g: 0.2, b: 0.3 });
+        world.insert_resource(Gravity{ y: -9.8 });
+        world.insert_resource(EditorGrid{ spacing: 2.0 });
+        common::save_ron(&world)
+    };
+    assert!(ron_str.contains("resources"));
+    assert!(!ron_str.contains("EditorGrid"));
+
+    let mut loaded = Scene::<Staged>::new();
+    register(&mut loaded);
+    let mut world = loaded.get_world().unwrap();
+    common::load_ron(&mut world, &ron_str);
+
+    assert_eq!(*world.resource::<ClearColor>(), ClearColor{ r: 0.1, g: 0.2, b: 0.3 });
+    assert_eq!(*world.resource::<Gravity>(), Gravity{ y: -9.8 });
+    assert!(world.get_resource::<EditorGrid>().is_none());
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use bevy_ecs::entity::EntityMap;
+use bevy_ecs::reflect::ReflectComponent;
+use bevy_ecs::world::World;
+use bevy_hierarchy::BuildWorldChildren;
+use bevy_reflect::TypeRegistryArc;
+use ember_math::{Vector3f, Vector4f};
+use serde::de::DeserializeSeed;
+
+use ember::core::plugins::components::{
+    AmbientLightingComponent,
+    AppInterfaceFlag,
+    CameraComponent,
+    DebugUiComponent,
+    DirectionalLightComponent,
+    FileSubMenuComponent,
+    GeometryComponent,
+    GeometryType,
+    InputComponent,
+    PrefabInstanceComponent,
+    PrefabMemberComponent,
+    PrefabOverride,
+    RenderableComponent,
+    SceneHandle,
+    SceneMemberComponent,
+    TerrainComponent,
+    TerrainUiComponent,
+    TransformBuilder,
+    TransformUiComponent,
+    VelocityComponent,
+};
+use ember::core::scene::scene_format::convert_scene;
+use ember::core::scene::MigrationRegistry;
+use ember::core::scene::{
+    DynamicScene,
+    Scene,
+    SceneDeserializer,
+    Staged,
+    TypeRegistryResource,
+};
+
+fn type_registry(world: &World) -> TypeRegistryArc {
+    world.resource::<TypeRegistryResource>().0.clone()
+}
+
+// one entity per component, plus a parent and child for the hierarchy components. entities are
+// spawned in file order on load so every world ends up with the same entity ids
+fn spawn_every_component(world: &mut World){
+    world.spawn(TransformBuilder::new()
+        .with_global_position(Vector3f::new(1.0, 2.0, 3.0))
+        .with_scale(2.5)
+        .build());
+    world.spawn(TransformUiComponent::default());
+    world.spawn(CameraComponent::default());
+    world.spawn(DirectionalLightComponent::new(
+        Vector3f::new(0.0, -1.0, 0.5),
+        Vector4f::new(1.0, 0.9, 0.8, 1.0),
+    ));
+    world.spawn(AmbientLightingComponent::new(Vector3f::new(0.1, 0.2, 0.3)));
+    world.spawn(GeometryComponent::create(GeometryType::Plane));
+    world.spawn(TerrainComponent::create(32));
+    world.spawn(TerrainUiComponent::default());
+    world.spawn(RenderableComponent::create());
+    world.spawn(VelocityComponent::default());
+    world.spawn(InputComponent::create());
+    world.spawn(DebugUiComponent::create());
+    world.spawn(SceneMemberComponent{ scene: SceneHandle(7) });
+    world.spawn(AppInterfaceFlag::default());
+    world.spawn(FileSubMenuComponent::default());
+
+    let parent = world.spawn(TransformBuilder::new().build()).id();
+    let child = world.spawn(TransformBuilder::new().with_scale(0.5).build()).id();
+    world.entity_mut(parent).push_children(&[child]);
+
+    world.spawn(PrefabInstanceComponent{
+        prefab: String::from("prefabs/crate.ron"),
+        overrides: vec![PrefabOverride{
+            entity: 0,
+            component: String::from("ember::core::plugins::components::transform_component::TransformComponent"),
+            field: String::from("scale"),
+            value: String::from("2.0"),
+        }],
+    });
+    world.spawn(PrefabMemberComponent{ instance: parent, source: 0 });
+}
+
+fn save(world: &World, path: &PathBuf) -> String {
+    let type_registry = type_registry(world);
+    DynamicScene::from_world(world, &type_registry)
+        .write_to_file(&path.to_string_lossy(), &type_registry)
+        .expect("couldn't save scene");
+    fs::read_to_string(path).expect("couldn't read saved scene")
+}
+
+fn load(world: &mut World, path: &PathBuf){
+    let type_registry = type_registry(world);
+    let ron_str = fs::read_to_string(path).expect("couldn't read saved scene");
+    let mut deserializer = ron::de::Deserializer::from_str(&ron_str).expect("invalid ron");
+    let scene = SceneDeserializer{ type_registry: &*type_registry.read(), migrations: None }
+        .deserialize(&mut deserializer)
+        .expect("couldn't deserialize scene");
+    scene
+        .write_to_world(world, &mut EntityMap::default())
+        .expect("couldn't spawn scene");
+}
+
+#[test]
+fn save_load_save_round_trips_every_registered_component(){
+    let dir = common::temp_project_dir("round_trip");
+    let first_path = dir.join("first.ron");
+    let second_path = dir.join("second.ron");
+
+    let mut original = Scene::<Staged>::new();
+    let first = {
+        let mut world = original.get_world().unwrap();
+        spawn_every_component(&mut world);
+        save(&world, &first_path)
+    };
+
+    let mut loaded = Scene::<Staged>::new();
+    let second = {
+        let mut world = loaded.get_world().unwrap();
+        load(&mut world, &first_path);
+        save(&world, &second_path)
+    };
+
+    // every registered component has to be in the file, otherwise it isn't being tested
+    let type_registry = {
+        let world = original.get_world().unwrap();
+        type_registry(&world)
+    };
+    for registration in type_registry.read().iter() {
+        if registration.data::<ReflectComponent>().is_none() {
+            continue;
+        }
+        assert!(
+            first.contains(registration.type_name()),
+            "{} is registered but wasn't saved",
+            registration.type_name(),
+        );
+    }
+
+    assert_eq!(first, second);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ron_to_binary_and_back_is_lossless(){
+    let dir = common::temp_project_dir("binary_round_trip");
+    let ron_path = dir.join("scene.ron");
+    let binary_path = dir.join("scene.scn");
+    let converted_path = dir.join("converted.ron");
+
+    let mut scene = Scene::<Staged>::new();
+    let mut world = scene.get_world().unwrap();
+    spawn_every_component(&mut world);
+    let original = save(&world, &ron_path);
+    let type_registry = type_registry(&world);
+
+    convert_scene(&ron_path.to_string_lossy(), &binary_path.to_string_lossy(), &type_registry, &MigrationRegistry::new()).unwrap();
+    convert_scene(&binary_path.to_string_lossy(), &converted_path.to_string_lossy(), &type_registry, &MigrationRegistry::new()).unwrap();
+
+    assert!(fs::metadata(&binary_path).unwrap().len() < original.len() as u64);
+    assert_eq!(original, fs::read_to_string(&converted_path).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use ember::core::plugins::components::{TerrainComponent, TerrainHeightMap, TerrainNoise};
+use ember::core::project::CurrentProject;
+use ember::core::rendering::geometries::terrain::write_height_map;
+use ember::core::scene::{Scene, Staged};
+use ember::core::systems::terrain_systems::{baked_heights_path, terrain_heights};
+
+fn terrain() -> TerrainComponent {
+    let mut terrain = TerrainComponent::create(24);
+    terrain.set_amplitude(3.5);
+    terrain.seed = 42;
+    terrain.noise = TerrainNoise{ frequency: 0.1, octaves: 3, lacunarity: 2.5, persistence: 0.4 };
+    terrain
+}
+
+// saves the terrain into a scene and loads it into a new world
+fn save_and_load(terrain: TerrainComponent) -> TerrainComponent {
+    let mut saved = Scene::<Staged>::new();
+    let ron_str = {
+        let mut world = saved.get_world().unwrap();
+        world.spawn(terrain);
+        common::save_ron(&world)
+    };
+
+    let mut loaded = Scene::<Staged>::new();
+    let mut world = loaded.get_world().unwrap();
+    common::load_ron(&mut world, &ron_str);
+    let mut query = world.query::<&TerrainComponent>();
+    let terrain = query.single(&world).clone();
+    terrain
+}
+
+// heights of a terrain that's been hand edited
+fn edited_heights(terrain: &mut TerrainComponent) -> Vec<f32> {
+    terrain.regenerate(None);
+    let mut heights = terrain.geometry.lock().unwrap().baked_heights();
+    heights[5] = 100.0;
+    terrain.regenerate(Some(&heights));
+    heights
+}
+
+// rebuilds the mesh the way TerrainInitSystem does when the scene is loaded
+fn rebuild(terrain: &TerrainComponent, project: Option<&CurrentProject>) -> Vec<f32> {
+    let heights = terrain_heights(terrain, project).expect("couldn't read the terrain's heights");
+    terrain.regenerate(heights.as_deref());
+    let baked = terrain.geometry.lock().unwrap().baked_heights();
+    baked
+}
+
+#[test]
+fn generation_parameters_are_saved_and_regenerate_the_same_mesh(){
+    let original = terrain();
+    original.regenerate(None);
+
+    let loaded = save_and_load(original.clone());
+    assert_eq!(loaded.get_size(), 24);
+    assert_eq!(loaded.get_amplitude(), 3.5);
+    assert_eq!(loaded.seed, 42);
+    assert_eq!(loaded.noise, original.noise);
+    assert_eq!(loaded.height_map, TerrainHeightMap::Generated);
+    assert_eq!(terrain_heights(&loaded, None).unwrap(), None);
+
+    let heights = original.geometry.lock().unwrap().baked_heights();
+    assert_eq!(heights.len(), 24 * 24);
+    assert_eq!(rebuild(&loaded, None), heights);
+}
+
+#[test]
+fn heights_baked_into_the_scene_are_kept_instead_of_generated(){
+    let mut original = terrain();
+    let heights = edited_heights(&mut original);
+    original.bake_height_map();
+
+    let loaded = save_and_load(original);
+    assert_eq!(loaded.height_map, TerrainHeightMap::Embedded(heights.clone()));
+    assert_eq!(rebuild(&loaded, None)[5], 100.0);
+}
+
+#[test]
+fn heights_baked_to_a_file_are_read_back_from_the_project(){
+    let dir = common::temp_project_dir("terrain");
+    let project = CurrentProject::new(dir.to_str().unwrap(), "level");
+
+    // what Bake to File does
+    let mut original = terrain();
+    let heights = edited_heights(&mut original);
+    let path = baked_heights_path(&original, &project);
+    assert_eq!(path, "terrain/level_1.heights");
+    write_height_map(&project.path.join(&path).to_string_lossy(), &heights).unwrap();
+    original.height_map = TerrainHeightMap::File(path.clone());
+    // baking again writes over the same file, another terrain gets its own
+    assert_eq!(baked_heights_path(&original, &project), path);
+    assert_eq!(baked_heights_path(&terrain(), &project), "terrain/level_2.heights");
+
+    let loaded = save_and_load(original);
+    assert_eq!(loaded.height_map, TerrainHeightMap::File(path));
+    assert_eq!(rebuild(&loaded, Some(&project))[5], 100.0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
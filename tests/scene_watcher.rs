@@ -0,0 +1,42 @@
+mod common;
+
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+
+use ember::core::scene::SceneWatcher;
+
+// pushes the modification time forward so the change shows on filesystems with coarse times
+fn touch(path: &std::path::Path, seconds: u64){
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(seconds))
+        .unwrap();
+}
+
+#[test]
+fn reports_scene_files_added_or_changed_since_the_last_check(){
+    let dir = common::temp_project_dir("scene_watcher");
+    fs::write(dir.join("level.ron"), "()").unwrap();
+    fs::write(dir.join("other.ron"), "()").unwrap();
+
+    let mut watcher = SceneWatcher::new(&dir, Duration::from_secs(60));
+    assert!(watcher.check().is_empty());
+
+    fs::write(dir.join("level.ron"), "(entities: {})").unwrap();
+    touch(&dir.join("level.ron"), 10);
+    fs::write(dir.join("new.scn"), [0u8]).unwrap();
+    // not a scene
+    fs::write(dir.join("notes.txt"), "").unwrap();
+    assert_eq!(watcher.check(), vec![dir.join("level.ron"), dir.join("new.scn")]);
+    assert!(watcher.check().is_empty());
+
+    // the first poll checks, the next waits for the interval
+    touch(&dir.join("other.ron"), 20);
+    assert_eq!(watcher.poll(), vec![dir.join("other.ron")]);
+    touch(&dir.join("other.ron"), 30);
+    assert!(watcher.poll().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
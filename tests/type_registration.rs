@@ -0,0 +1,99 @@
+mod common;
+
+use std::any::{type_name, TypeId};
+
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_reflect::{Reflect, TypeRegistry};
+use log::LevelFilter;
+
+use ember::core::application::application_manager::Application;
+use ember::core::plugins::components::TransformComponent;
+use ember::core::project::project_template::create_project;
+use ember::core::scene::type_registration::{registered_types, RegisteredTypeKind};
+use ember::core::scene::{DynamicScene, Scene, Staged, TypeRegistryResource};
+use ember::Plugin;
+
+// registers itself like the engine's own components
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct LinkedComponent{
+    value: u32,
+}
+
+ember::register_type!(LinkedComponent);
+
+// only known through the plugin below
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct PluginComponent{
+    value: u32,
+}
+
+struct TestPlugin;
+
+impl Plugin for TestPlugin{
+    fn name(&self) -> &'static str {
+        "test plugin"
+    }
+
+    fn register_types(&self, registry: &mut TypeRegistry) {
+        registry.register::<PluginComponent>();
+    }
+}
+
+fn lists<T: 'static>(registry: &TypeRegistry) -> bool {
+    registered_types(registry).contains(&(RegisteredTypeKind::Component, String::from(type_name::<T>())))
+}
+
+fn plugin_values(app: &Application) -> Vec<u32> {
+    let mut scene = app.get_active_scene().unwrap();
+    let mut world = scene.get_world().unwrap();
+    let mut query = world.query::<&PluginComponent>();
+    query.iter(&world).map(|c| c.value).collect()
+}
+
+#[test]
+fn self_registered_types_reach_every_scene(){
+    let mut scene = Scene::<Staged>::new();
+    let world = scene.get_world().unwrap();
+    let registry = world.resource::<TypeRegistryResource>().0.read();
+    assert!(registry.get(TypeId::of::<TransformComponent>()).is_some());
+    assert!(registry.get(TypeId::of::<LinkedComponent>()).is_some());
+    assert!(lists::<LinkedComponent>(&registry));
+    assert!(!lists::<PluginComponent>(&registry));
+}
+
+#[test]
+fn plugin_types_reach_the_application_and_staged_scenes(){
+    let dir = common::temp_project_dir("type_registration");
+    let project_path = dir.join("game");
+    create_project(project_path.to_str().unwrap(), "level").unwrap();
+
+    // a scene saved by an editor that had the plugin loaded
+    let mut saved = Scene::<Staged>::new();
+    {
+        let mut world = saved.get_world().unwrap();
+        let type_registry = world.resource::<TypeRegistryResource>().0.clone();
+        TestPlugin.register_types(&mut type_registry.write());
+        world.spawn(PluginComponent{ value: 4 });
+        DynamicScene::from_world(&world, &type_registry)
+            .write_to_file(project_path.join("scenes").join("level.ron").to_str().unwrap(), &type_registry)
+            .unwrap();
+    }
+
+    let mut app = Application::create_headless_application(LevelFilter::Warn);
+    app.add_plugin(Box::new(TestPlugin));
+    assert!(app.type_registry().read().get(TypeId::of::<PluginComponent>()).is_some());
+    assert!(lists::<PluginComponent>(&app.type_registry().read()));
+
+    // the project's scene is staged with the application's registry, so it can read the plugin's
+    // component
+    app.open_project(project_path.to_string_lossy().to_string(), None);
+    assert!(common::run_until(&mut app, |app| !plugin_values(app).is_empty()));
+    assert_eq!(plugin_values(&app), vec![4]);
+    let mut scene = app.get_active_scene().unwrap();
+    let world = scene.get_world().unwrap();
+    assert!(lists::<PluginComponent>(&world.resource::<TypeRegistryResource>().0.read()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}